@@ -1,28 +1,189 @@
 use chrono::NaiveDateTime;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use tauri_plugin_fs::FilePath;
 use ts_rs::TS;
 
-#[derive(Default)]
+use crate::uncovering::Uncovering;
+
+/// Largest seed that survives being passed through JavaScript's numbers, i.e. 2^53 - 1.
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
 pub struct RevealState {
     pub images: Vec<FilePath>,
+    /// Whether `images` are shown in a given order, e.g. a deck's, instead of being shuffled.
+    pub keep_order: bool,
     pub image_index: usize,
     pub settings: RevealSettings,
+    /// The seed `rng` has last been initialized with.
+    pub seed: u64,
+    /// Single source of randomness for the game, such that a game can be replayed.
+    pub rng: ChaCha8Rng,
+    pub uncovering: Uncovering,
+    /// Raw bytes of the image currently shown, e.g. for analyzing its content.
+    pub current_image: Vec<u8>,
+    /// Key regions of the image currently shown.
+    pub key_regions: Vec<KeyRegion>,
+    /// For images loaded from a folder, the subfolder each one has been found in.
+    pub subfolders: HashMap<PathBuf, PathBuf>,
+}
+
+impl Default for RevealState {
+    fn default() -> Self {
+        let mut state = RevealState {
+            images: Vec::new(),
+            keep_order: false,
+            image_index: 0,
+            settings: RevealSettings::default(),
+            seed: 0,
+            rng: ChaCha8Rng::seed_from_u64(0),
+            uncovering: Uncovering::default(),
+            current_image: Vec::new(),
+            key_regions: Vec::new(),
+            subfolders: HashMap::new(),
+        };
+        state.reseed();
+        state
+    }
+}
+
+impl RevealState {
+    /// Restarts the random number generator, either with the seed
+    /// from the settings or with a fresh random seed.
+    pub fn reseed(&mut self) {
+        self.seed = self
+            .settings
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().gen_range(0..=MAX_SEED));
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+        log::debug!("Using seed {}.", self.seed);
+    }
+
+    /// Restarts the random number generator and shuffles the loaded images again,
+    /// exactly as if they had just been loaded.
+    pub fn reshuffle(&mut self) {
+        self.reseed();
+        if !self.keep_order {
+            shuffle_images(&mut self.images, &mut self.rng);
+        }
+        self.image_index = 0;
+    }
+}
+
+pub fn shuffle_images(images: &mut [FilePath], rng: &mut impl Rng) {
+    // Sort first, such that the order does not depend on how the paths were listed.
+    images.sort_by_key(|fp| fp.to_string());
+    images.shuffle(rng);
 }
 
-#[derive(Clone, Default, Serialize, TS)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum CoveringType {
     #[default]
     Triangles,
     Rectangles,
+    UnevenRectangles,
+    Quadtree,
+    Jigsaw,
+    Rings,
+    Voronoi,
+    Hexagons,
+    Pixelation,
+    Blur,
+    Zoom,
+    Scramble,
 }
 
-#[derive(Clone, Default, Serialize, TS)]
+/// How the points of point-based coverings, e.g. triangles, are placed.
+#[derive(Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PointSampling {
+    /// One random point per cell of a regular grid.
+    #[default]
+    Grid,
+    /// Blue noise, i.e. points keep a minimum distance to each other.
+    PoissonDisk {
+        /// Relative to the shorter side of the image.
+        /// Derived from the number of objects if not set.
+        min_distance: Option<f64>,
+    },
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum UncoveringStrategy {
+    /// The user uncovers polygon by polygon.
     #[default]
     Manual,
+    /// The backend uncovers the next polygon after each interval.
+    Timed { interval_ms: u64 },
+}
+
+/// The order in which the polygons of a covering are uncovered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum UncoveringOrder {
+    #[default]
+    Random,
+    /// Closest to the center (or focus) first.
+    CenterOut,
+    /// Farthest from the center (or focus) first.
+    EdgesIn,
+    /// Along a spiral, starting at the center (or focus).
+    Spiral,
+    LeftToRight,
+    TopToBottom,
+    /// Plain parts of the image first, the parts with the most details last.
+    Saliency,
+}
+
+/// Kinds of questions that can be asked about an image, mostly based on when it was taken.
+#[derive(Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum QuestionType {
+    Year,
+    Month,
+    Season,
+    Weekday,
+    TimeOfDay,
+    YearsAgo,
+    /// Based on where it was taken instead.
+    Location,
+    /// Based on the name of the folder containing the image.
+    Folder,
+    /// Based on the image's file name.
+    FileName,
+    /// Written by hand in the image's sidecar file.
+    Custom,
+}
+
+/// How folders are scanned for images.
+#[derive(Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FolderScan {
+    /// How deep to descend into subfolders, 0 only considers the folder itself.
+    pub max_depth: usize,
+    /// Glob patterns relative to the folder, e.g. '2024/**'. All files are included if empty.
+    pub include: Vec<String>,
+    /// Glob patterns relative to the folder, matching files or whole subfolders.
+    pub exclude: Vec<String>,
+    /// Whether to skip folders whose names start with a dot.
+    pub skip_hidden: bool,
+}
+
+impl Default for FolderScan {
+    fn default() -> Self {
+        FolderScan {
+            max_depth: 0,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            skip_hidden: true,
+        }
+    }
 }
 
 #[derive(Clone, Serialize, TS)]
@@ -32,8 +193,16 @@ pub struct RevealSettings {
     pub covering_type: CoveringType,
     /// Approximate number of objects to cover the image with.
     pub covering_object_count: usize,
+    pub point_sampling: PointSampling,
     pub uncovering_strategy: UncoveringStrategy,
+    pub uncovering_order: UncoveringOrder,
+    pub folder_scan: FolderScan,
+    /// Larger images are downscaled to this width and height before they are sent.
+    /// The size of the largest monitor is used if not set.
+    pub max_resolution: Option<u32>,
     pub show_control_buttons: bool,
+    /// Makes games reproducible. A random seed is used if not set.
+    pub seed: Option<u64>,
 }
 
 impl Default for RevealSettings {
@@ -42,8 +211,13 @@ impl Default for RevealSettings {
             image_source: None,
             covering_type: CoveringType::Rectangles,
             covering_object_count: 10,
+            point_sampling: PointSampling::Grid,
             uncovering_strategy: UncoveringStrategy::Manual,
+            uncovering_order: UncoveringOrder::Random,
+            folder_scan: FolderScan::default(),
+            max_resolution: None,
             show_control_buttons: true,
+            seed: None,
         }
     }
 }
@@ -58,6 +232,47 @@ pub struct RevealObject {
     pub question: Option<String>,
     pub answers: Vec<String>,
     pub correct_answer: usize,
+    /// Revealed one after another on request.
+    pub hints: Vec<String>,
+    /// Shown once the question has been answered.
+    pub explanation: Option<String>,
+    /// Subfolder of the folder the images are loaded from, if any.
+    pub subfolder: Option<String>,
+    /// Crops for the zoom-out reveal mode, from the smallest one to the
+    /// largest one. The full image is not included, it is the final step.
+    pub crops: Vec<Crop>,
+}
+
+/// Pre-rendered frames for non-geometric reveal modes,
+/// shown one after another instead of uncovering polygons.
+#[derive(Default, Serialize, TS)]
+#[ts(export)]
+pub struct RevealFrames {
+    /// Base64 encoded frames, from the most to the least degraded one.
+    pub frames: Vec<String>,
+    /// MIME subtype of the frames, e.g. 'jpeg'
+    pub image_type: String,
+}
+
+/// Tile-scramble puzzle: the image is cut into tiles whose positions are shuffled.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Scramble {
+    /// Tiles in their correct place.
+    pub tiles: Vec<Polygon>,
+    /// `slots[i]` is the tile initially shown at the place of tile `i`.
+    pub slots: Vec<usize>,
+    /// Each step swaps the contents of two slots, putting a tile back in place.
+    pub swaps: Vec<(usize, usize)>,
+}
+
+/// A section of an image, given relative to the image's size.
+#[derive(Debug, Serialize, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct Crop {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
 }
 
 /// Represents a point in 2D space.
@@ -73,12 +288,68 @@ pub struct Polygon {
     pub pnts: Vec<Point>,
 }
 
+/// A part of an image that gives away its content, given relative
+/// to the image's size such that it is independent of the resolution.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct KeyRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Polygons intersecting the region are not uncovered before this step.
+    /// If not set, they are uncovered last.
+    #[serde(default)]
+    pub not_before: Option<usize>,
+}
+
+/// A question about an image written by hand.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CustomQuestion {
+    pub question: String,
+    pub answers: Vec<String>,
+    /// Index of the correct answer.
+    pub correct: usize,
+    #[serde(default)]
+    pub hints: Vec<String>,
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Where an image was taken, in decimal degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpsLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Name describing an image, e.g. derived from its path,
+/// along with names of the same kind that describe other images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub name: String,
+    pub siblings: Vec<String>,
+}
+
 /// Represents an image with associated metadata.
 pub struct ImageWithMeta {
+    /// The raw image data.
+    pub bytes: Vec<u8>,
     /// The base64-encoded image data.
     pub base64: String,
     /// The type of the image (e.g., "jpeg", "png").
     pub image_type: String,
     /// The date and time when the image was taken.
     pub date_taken: Option<NaiveDateTime>,
+    /// Where the image was taken.
+    pub location: Option<GpsLocation>,
+    /// Name of the folder containing the image.
+    pub folder_label: Option<Label>,
+    /// Name derived from the image's file name.
+    pub file_label: Option<Label>,
+    /// Regions that shall be uncovered late.
+    pub key_regions: Vec<KeyRegion>,
+    /// Questions written by hand.
+    pub questions: Vec<CustomQuestion>,
+    /// Subfolder the image has been found in when scanning a folder.
+    pub subfolder: Option<PathBuf>,
 }
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+
+use crate::common::{CoveringType, UncoveringOrder};
+use crate::sidecar::sidecar_path;
+
+/// File extension of quiz decks, which are zip archives of images and a manifest.
+pub const DECK_EXTENSION: &str = "reveal";
+const MANIFEST: &str = "manifest.json";
+/// Range of the number of objects, as offered in the settings.
+const OBJECT_COUNTS: std::ops::RangeInclusive<usize> = 10..=100;
+
+/// Settings a deck brings along, taking precedence over the user's settings while it is played.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DeckSettings {
+    pub title: Option<String>,
+    /// E.g. 'Jigsaw', unknown covering types render the manifest invalid.
+    pub covering_type: Option<CoveringType>,
+    pub object_count: Option<usize>,
+    /// E.g. 'CenterOut', unknown uncovering orders render the manifest invalid.
+    pub uncovering_order: Option<UncoveringOrder>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Manifest {
+    #[serde(flatten)]
+    settings: DeckSettings,
+    images: Vec<DeckImage>,
+}
+
+/// An image of a deck. Anything besides the file name, e.g. questions,
+/// has the same format as a sidecar file.
+#[derive(Debug, Deserialize)]
+struct DeckImage {
+    file: String,
+    #[serde(flatten)]
+    sidecar: serde_json::Map<String, serde_json::Value>,
+}
+
+pub struct Deck {
+    pub settings: DeckSettings,
+    pub images: Vec<PathBuf>,
+    /// Whether the manifest lists the images, and hence defines their order.
+    pub ordered: bool,
+}
+
+/// Extracts a deck into the `target` folder, replacing previous contents, and turns
+/// the manifest's per-image entries into sidecar files next to the images.
+pub fn open_deck(deck: &Path, target: &Path) -> Result<Deck, String> {
+    log::debug!("Extracting deck {:?} to {:?}", deck, target);
+    let file = std::fs::File::open(deck).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    if target.exists() {
+        std::fs::remove_dir_all(target).map_err(|e| e.to_string())?;
+    }
+    archive.extract(target).map_err(|e| e.to_string())?;
+
+    let manifest_path = target.join(MANIFEST);
+    let manifest: Manifest = if manifest_path.is_file() {
+        std::fs::read_to_string(&manifest_path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Invalid manifest of '{}': {}", deck.display(), e))?
+    } else {
+        Manifest::default()
+    };
+    if let Some(count) = manifest.settings.object_count {
+        if !OBJECT_COUNTS.contains(&count) {
+            return Err(format!(
+                "Invalid manifest of '{}': object_count {} is not between {} and {}.",
+                deck.display(),
+                count,
+                OBJECT_COUNTS.start(),
+                OBJECT_COUNTS.end()
+            ));
+        }
+    }
+
+    if manifest.images.is_empty() {
+        // Without a list, the deck is used like a folder.
+        let images = std::fs::read_dir(target)
+            .map_err(|e| e.to_string())?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && *path != manifest_path)
+            .collect();
+        return Ok(Deck {
+            settings: manifest.settings,
+            images,
+            ordered: false,
+        });
+    }
+
+    let images = manifest
+        .images
+        .into_iter()
+        .map(|image| {
+            // Don't follow file names out of the deck.
+            let file = Path::new(&image.file);
+            if !file.components().all(|c| matches!(c, Component::Normal(_))) {
+                return Err(format!("Invalid image '{}' in manifest.", image.file));
+            }
+            let path = target.join(file);
+            if !image.sidecar.is_empty() {
+                let sidecar = serde_json::to_string(&image.sidecar).map_err(|e| e.to_string())?;
+                std::fs::write(sidecar_path(&path), sidecar).map_err(|e| e.to_string())?;
+            }
+            Ok(path)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Deck {
+        settings: manifest.settings,
+        images,
+        ordered: true,
+    })
+}
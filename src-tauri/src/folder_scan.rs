@@ -0,0 +1,75 @@
+use glob::Pattern;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::common::FolderScan;
+
+/// A file found while scanning a folder.
+pub struct ScannedFile {
+    /// Canonical path of the file.
+    pub path: PathBuf,
+    /// Relative to the scanned folder, empty for the folder's direct children.
+    pub subfolder: PathBuf,
+}
+
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|p| Pattern::new(p).map_err(|e| format!("Invalid pattern '{p}': {e}")))
+        .collect()
+}
+
+/// Collects the files within a folder and, up to `max_depth`, its subfolders.
+/// Patterns are matched against paths relative to the folder, excluded folders
+/// are not entered at all.
+pub fn scan_folder(root: &Path, scan: &FolderScan) -> Result<Vec<ScannedFile>, String> {
+    let include = compile_patterns(&scan.include)?;
+    let exclude = compile_patterns(&scan.exclude)?;
+
+    let mut visited = HashSet::new();
+    let mut files = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), PathBuf::new(), 0)];
+    while let Some((folder, subfolder, depth)) = pending.pop() {
+        // Symlinks may lead back to a folder that has been scanned already.
+        let Ok(canonical) = std::fs::canonicalize(&folder) else {
+            continue;
+        };
+        if !visited.insert(canonical) {
+            log::debug!("Skipping '{:?}', it has been scanned already.", folder);
+            continue;
+        }
+
+        let entries = match std::fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("Couldn't load from path '{:?}': {:?}", folder, e);
+                continue;
+            }
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let relative = subfolder.join(entry.file_name());
+            if exclude.iter().any(|p| p.matches_path(&relative)) {
+                continue;
+            }
+
+            if path.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if depth < scan.max_depth && !(hidden && scan.skip_hidden) {
+                    pending.push((path, relative, depth + 1));
+                }
+            } else if path.is_file()
+                && (include.is_empty() || include.iter().any(|p| p.matches_path(&relative)))
+            {
+                if let Ok(path) = std::fs::canonicalize(&path) {
+                    files.push(ScannedFile {
+                        path,
+                        subfolder: subfolder.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
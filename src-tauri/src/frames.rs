@@ -0,0 +1,119 @@
+use base64::engine::{general_purpose, Engine as _};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use rand::Rng;
+use std::io::Cursor;
+
+use crate::common::{Crop, KeyRegion, Point, RevealFrames};
+
+/// Frames are rendered at this (maximum) resolution. They are degraded anyway,
+/// hence there's no need to transfer them in full resolution.
+const FRAME_RESOLUTION: u32 = 512;
+/// Upper limit for the number of frames, since each of them has to be transferred.
+const MAX_FRAMES: usize = 30;
+/// Relative size of the first crop of the zoom-out mode, i.e. it shows 1% of the image.
+const ZOOM_START_SIZE: f64 = 0.1;
+/// Random points tried as center of the zoom-out mode, to find one away from the key regions.
+const ZOOM_START_CANDIDATES: usize = 100;
+
+/// Non-geometric ways of revealing an image.
+pub enum FrameMode {
+    /// Starts with huge pixels that become smaller and smaller.
+    Pixelation,
+    /// Starts blurry and becomes sharper and sharper.
+    Blur,
+}
+
+/// Renders up to `steps` increasingly less degraded versions of an image.
+/// The original image itself is not included, it is the final step.
+pub fn render_frames(bytes: &[u8], steps: usize, mode: &FrameMode) -> Result<RevealFrames, String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| e.to_string())?
+        .resize(FRAME_RESOLUTION, FRAME_RESOLUTION, FilterType::Triangle);
+    let steps = steps.clamp(1, MAX_FRAMES);
+    let largest_side = image.width().max(image.height()) as f64;
+
+    let frames = (0..steps)
+        .map(|step| {
+            // 1.0 for the first, most degraded frame, approaching 0.0 towards the original.
+            let degradation = 1.0 - step as f64 / steps as f64;
+            let frame = match mode {
+                FrameMode::Pixelation => {
+                    let block = (largest_side / 8.0).powf(degradation).max(1.0);
+                    let (w, h) = (image.width(), image.height());
+                    image
+                        .resize_exact(
+                            ((w as f64 / block).ceil() as u32).max(1),
+                            ((h as f64 / block).ceil() as u32).max(1),
+                            FilterType::Triangle,
+                        )
+                        .resize_exact(w, h, FilterType::Nearest)
+                }
+                FrameMode::Blur => {
+                    image.fast_blur((largest_side / 20.0 * degradation.powi(2)) as f32)
+                }
+            };
+            encode_jpeg(&frame)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    log::debug!("Rendered {} frames.", frames.len());
+
+    Ok(RevealFrames {
+        frames,
+        image_type: "jpeg".into(),
+    })
+}
+
+/// Computes `steps` crops that zoom out of an image step by step.
+/// They are centered on a random point, away from the key regions if possible,
+/// since those give away the image's content and are meant to be revealed last.
+pub fn zoom_crops(steps: usize, key_regions: &[KeyRegion], rng: &mut impl Rng) -> Vec<Crop> {
+    let margin = ZOOM_START_SIZE / 2.0;
+    // The first candidate whose first crop overlaps the key regions the least.
+    let center = (0..ZOOM_START_CANDIDATES)
+        .map(|_| Point {
+            x: rng.gen_range(margin..=1.0 - margin),
+            y: rng.gen_range(margin..=1.0 - margin),
+        })
+        .map(|center| {
+            let overlap = key_regions
+                .iter()
+                .map(|region| {
+                    let width = (center.x + margin).min(region.x + region.width)
+                        - (center.x - margin).max(region.x);
+                    let height = (center.y + margin).min(region.y + region.height)
+                        - (center.y - margin).max(region.y);
+                    width.max(0.0) * height.max(0.0)
+                })
+                .sum::<f64>();
+            (center, overlap)
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(center, _)| center)
+        .unwrap();
+    let steps = steps.max(1);
+
+    (0..steps)
+        .map(|step| {
+            // Grow geometrically, such that each step reveals a similar share of what's new.
+            let size = ZOOM_START_SIZE.powf(1.0 - step as f64 / steps as f64);
+            // Shift the crop instead of leaving the image.
+            Crop {
+                x: (center.x - size / 2.0).clamp(0.0, 1.0 - size),
+                y: (center.y - size / 2.0).clamp(0.0, 1.0 - size),
+                width: size,
+                height: size,
+            }
+        })
+        .collect()
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    // JPEG does not support transparency.
+    DynamicImage::ImageRgb8(image.to_rgb8())
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg)
+        .map_err(|e| e.to_string())?;
+    Ok(general_purpose::STANDARD.encode(&bytes))
+}
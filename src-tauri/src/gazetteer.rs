@@ -0,0 +1,162 @@
+use crate::common::GpsLocation;
+
+/// Mean radius of the earth in kilometers.
+const EARTH_RADIUS: f64 = 6371.0;
+
+/// Great-circle distance in kilometers, using the haversine formula.
+pub fn distance(a: &GpsLocation, b: &GpsLocation) -> f64 {
+    let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+/// Names of all places, e.g. "Paris, France", sorted by their distance to the given location.
+pub fn places_by_distance(location: &GpsLocation) -> Vec<(f64, String)> {
+    let mut places = PLACES
+        .iter()
+        .map(|(city, country, latitude, longitude)| {
+            let place = GpsLocation {
+                latitude: *latitude,
+                longitude: *longitude,
+            };
+            (distance(location, &place), format!("{city}, {country}"))
+        })
+        .collect::<Vec<_>>();
+    places.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    places
+}
+
+/// Capitals and major cities as city, country, latitude and longitude.
+/// Coarse, but small enough to be bundled.
+const PLACES: [(&str, &str, f64, f64); 122] = [
+    // Europe
+    ("Amsterdam", "Netherlands", 52.37, 4.90),
+    ("Athens", "Greece", 37.98, 23.73),
+    ("Barcelona", "Spain", 41.39, 2.17),
+    ("Belgrade", "Serbia", 44.79, 20.45),
+    ("Berlin", "Germany", 52.52, 13.40),
+    ("Bern", "Switzerland", 46.95, 7.45),
+    ("Brussels", "Belgium", 50.85, 4.35),
+    ("Bucharest", "Romania", 44.43, 26.10),
+    ("Budapest", "Hungary", 47.50, 19.04),
+    ("Copenhagen", "Denmark", 55.68, 12.57),
+    ("Dublin", "Ireland", 53.35, -6.26),
+    ("Edinburgh", "United Kingdom", 55.95, -3.19),
+    ("Florence", "Italy", 43.77, 11.26),
+    ("Frankfurt", "Germany", 50.11, 8.68),
+    ("Geneva", "Switzerland", 46.20, 6.14),
+    ("Hamburg", "Germany", 53.55, 9.99),
+    ("Helsinki", "Finland", 60.17, 24.94),
+    ("Istanbul", "Turkey", 41.01, 28.98),
+    ("Kyiv", "Ukraine", 50.45, 30.52),
+    ("Lisbon", "Portugal", 38.72, -9.14),
+    ("Ljubljana", "Slovenia", 46.06, 14.51),
+    ("London", "United Kingdom", 51.51, -0.13),
+    ("Lyon", "France", 45.76, 4.84),
+    ("Madrid", "Spain", 40.42, -3.70),
+    ("Manchester", "United Kingdom", 53.48, -2.24),
+    ("Marseille", "France", 43.30, 5.37),
+    ("Milan", "Italy", 45.46, 9.19),
+    ("Moscow", "Russia", 55.76, 37.62),
+    ("Munich", "Germany", 48.14, 11.58),
+    ("Naples", "Italy", 40.85, 14.27),
+    ("Oslo", "Norway", 59.91, 10.75),
+    ("Paris", "France", 48.86, 2.35),
+    ("Porto", "Portugal", 41.15, -8.61),
+    ("Prague", "Czechia", 50.08, 14.44),
+    ("Reykjavik", "Iceland", 64.15, -21.94),
+    ("Riga", "Latvia", 56.95, 24.11),
+    ("Rome", "Italy", 41.90, 12.50),
+    ("Seville", "Spain", 37.39, -5.98),
+    ("Sofia", "Bulgaria", 42.70, 23.32),
+    ("Stockholm", "Sweden", 59.33, 18.07),
+    ("Tallinn", "Estonia", 59.44, 24.75),
+    ("Valletta", "Malta", 35.90, 14.51),
+    ("Venice", "Italy", 45.44, 12.32),
+    ("Vienna", "Austria", 48.21, 16.37),
+    ("Vilnius", "Lithuania", 54.69, 25.28),
+    ("Warsaw", "Poland", 52.23, 21.01),
+    ("Zagreb", "Croatia", 45.81, 15.98),
+    ("Zurich", "Switzerland", 47.38, 8.54),
+    // Africa
+    ("Accra", "Ghana", 5.60, -0.19),
+    ("Addis Ababa", "Ethiopia", 9.03, 38.74),
+    ("Algiers", "Algeria", 36.75, 3.06),
+    ("Cairo", "Egypt", 30.04, 31.24),
+    ("Cape Town", "South Africa", -33.92, 18.42),
+    ("Casablanca", "Morocco", 33.57, -7.59),
+    ("Dakar", "Senegal", 14.72, -17.47),
+    ("Dar es Salaam", "Tanzania", -6.79, 39.21),
+    ("Johannesburg", "South Africa", -26.20, 28.05),
+    ("Kinshasa", "DR Congo", -4.44, 15.27),
+    ("Lagos", "Nigeria", 6.52, 3.38),
+    ("Marrakesh", "Morocco", 31.63, -7.99),
+    ("Nairobi", "Kenya", -1.29, 36.82),
+    ("Tunis", "Tunisia", 36.81, 10.18),
+    ("Windhoek", "Namibia", -22.56, 17.08),
+    // Asia
+    ("Bangkok", "Thailand", 13.76, 100.50),
+    ("Beijing", "China", 39.90, 116.41),
+    ("Colombo", "Sri Lanka", 6.93, 79.86),
+    ("Delhi", "India", 28.61, 77.21),
+    ("Dubai", "United Arab Emirates", 25.20, 55.27),
+    ("Hanoi", "Vietnam", 21.03, 105.85),
+    ("Ho Chi Minh City", "Vietnam", 10.82, 106.63),
+    ("Hong Kong", "China", 22.32, 114.17),
+    ("Jakarta", "Indonesia", -6.21, 106.85),
+    ("Jerusalem", "Israel", 31.77, 35.21),
+    ("Kathmandu", "Nepal", 27.72, 85.32),
+    ("Kuala Lumpur", "Malaysia", 3.139, 101.687),
+    ("Kyoto", "Japan", 35.01, 135.77),
+    ("Manila", "Philippines", 14.60, 120.98),
+    ("Mumbai", "India", 19.08, 72.88),
+    ("Osaka", "Japan", 34.69, 135.50),
+    ("Seoul", "South Korea", 37.57, 126.98),
+    ("Shanghai", "China", 31.23, 121.47),
+    ("Singapore", "Singapore", 1.35, 103.82),
+    ("Taipei", "Taiwan", 25.03, 121.57),
+    ("Tashkent", "Uzbekistan", 41.30, 69.24),
+    ("Tbilisi", "Georgia", 41.72, 44.79),
+    ("Tehran", "Iran", 35.69, 51.39),
+    ("Tokyo", "Japan", 35.68, 139.69),
+    ("Ulaanbaatar", "Mongolia", 47.89, 106.91),
+    // North and Central America
+    ("Boston", "United States", 42.36, -71.06),
+    ("Chicago", "United States", 41.88, -87.63),
+    ("Denver", "United States", 39.74, -104.99),
+    ("Havana", "Cuba", 23.11, -82.37),
+    ("Honolulu", "United States", 21.31, -157.86),
+    ("Las Vegas", "United States", 36.17, -115.14),
+    ("Los Angeles", "United States", 34.05, -118.24),
+    ("Mexico City", "Mexico", 19.43, -99.13),
+    ("Miami", "United States", 25.76, -80.19),
+    ("Montreal", "Canada", 45.50, -73.57),
+    ("New Orleans", "United States", 29.95, -90.07),
+    ("New York", "United States", 40.71, -74.01),
+    ("Panama City", "Panama", 8.98, -79.52),
+    ("San Francisco", "United States", 37.77, -122.42),
+    ("San José", "Costa Rica", 9.93, -84.08),
+    ("Seattle", "United States", 47.61, -122.33),
+    ("Toronto", "Canada", 43.65, -79.38),
+    ("Vancouver", "Canada", 49.28, -123.12),
+    ("Washington", "United States", 38.91, -77.04),
+    // South America
+    ("Bogotá", "Colombia", 4.71, -74.07),
+    ("Buenos Aires", "Argentina", -34.60, -58.38),
+    ("Cusco", "Peru", -13.53, -71.97),
+    ("Lima", "Peru", -12.05, -77.04),
+    ("Montevideo", "Uruguay", -34.90, -56.16),
+    ("Quito", "Ecuador", -0.18, -78.47),
+    ("Rio de Janeiro", "Brazil", -22.91, -43.17),
+    ("Santiago", "Chile", -33.45, -70.67),
+    ("São Paulo", "Brazil", -23.55, -46.63),
+    // Oceania
+    ("Auckland", "New Zealand", -36.85, 174.76),
+    ("Brisbane", "Australia", -27.47, 153.03),
+    ("Melbourne", "Australia", -37.81, 144.96),
+    ("Perth", "Australia", -31.95, 115.86),
+    ("Sydney", "Australia", -33.87, 151.21),
+    ("Wellington", "New Zealand", -41.29, 174.78),
+];
@@ -1,22 +1,28 @@
 use base64::engine::{general_purpose, Engine as _};
 use chrono::NaiveDateTime;
-use exif::{In, Reader, Tag};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use exif::{Exif, In, Reader, Tag, Value};
+use image::metadata::Orientation;
 use rand::Rng;
 use serde_json::json;
 use std::{
+    io::Read,
     path::{Path, PathBuf},
     sync::Mutex,
 };
 use tauri::Emitter;
 use tauri::{AppHandle, Manager, State};
-use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_fs::FilePath;
 use tauri_plugin_fs::FsExt;
 use tauri_plugin_store::StoreExt;
 
-use crate::common::{ImageWithMeta, RevealState};
+use crate::common::{shuffle_images, GpsLocation, ImageWithMeta, RevealState};
+use crate::deck::{open_deck, DECK_EXTENSION};
+use crate::folder_scan::scan_folder;
+use crate::labels::{file_label, folder_label};
+use crate::sidecar::{read_sidecar, Sidecar};
+use crate::transcoding::{exceeds_resolution, transcode, TRANSCODED_IMAGE_EXTENSIONS};
+use crate::utils::largest_monitor_size;
 
 #[derive(Debug)]
 enum FolderOrFiles {
@@ -30,6 +36,14 @@ const SUPPORTED_IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "gi
 #[cfg(not(desktop))]
 const SUPPORTED_IMAGE_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
 
+/// Images that are only supported by builds with the corresponding feature,
+/// see `TRANSCODED_IMAGE_EXTENSIONS`. Others are skipped with a warning.
+const OPTIONAL_IMAGE_EXTENSIONS: [&str; 3] = ["avif", "heif", "heic"];
+
+/// Number of bytes read from the start of a file to detect its type,
+/// plenty for magic numbers and enough to find the root element of most SVGs.
+const HEADER_SIZE: u64 = 4096;
+
 fn exists_is_dir_and_non_empty(path: &Path) -> bool {
     path.exists()
         && path.is_dir()
@@ -204,7 +218,14 @@ fn get_image_paths_user(
                 // at this point, must list all supported types
                 picker = picker.add_filter(
                     "images_file_picker",
-                    &["image/jpeg", "image/png", "image/webp", "text/plain"],
+                    &[
+                        "image/jpeg",
+                        "image/png",
+                        "image/webp",
+                        "image/tiff",
+                        "image/bmp",
+                        "text/plain",
+                    ],
                 );
             } else {
                 // "image/jpeg" --> photo picker (also allows pngs and webp at this point)
@@ -248,10 +269,10 @@ pub fn get_image_paths(
             store.set("loaded_from_folder", json!(folder.as_path().unwrap()));
 
             let filtered_and_shuffled_paths = match folder.clone() {
-                FilePath::Path(pb) => Some(load_from_folder(pb))
+                FilePath::Path(pb) => Some(load_from_folder(app, pb))
                     .map(|t| filter_to_supported_images(app, &t))
                     .map(|mut v| {
-                        shuffle(&mut v);
+                        shuffle(app, &mut v);
                         v
                     })
                     .unwrap(),
@@ -261,11 +282,19 @@ pub fn get_image_paths(
             Ok((Some(folder.clone()), filtered_and_shuffled_paths))
         }
         Ok(FolderOrFiles::Files(files)) => {
+            // Hand-selected images and decks are not part of a scanned folder.
+            let state = app.state::<Mutex<RevealState>>();
+            state.lock().unwrap().subfolders.clear();
+
+            if let Some(deck) = files.iter().find_map(deck_path) {
+                return load_deck(app, deck);
+            }
+
             let number_of_selected = files.len();
             let filtered_and_shuffled_paths = Some(files)
                 .map(|t| filter_to_supported_images(app, &t))
                 .map(|mut v| {
-                    shuffle(&mut v);
+                    shuffle(app, &mut v);
                     v
                 })
                 .unwrap();
@@ -298,18 +327,71 @@ pub fn get_image_paths(
     }
 }
 
-fn load_from_folder(folder_path: PathBuf) -> Vec<FilePath> {
+/// Decks are desktop-only for now, mobile pickers yield urls without extension
+/// (Android) or don't offer files other than images at all (iOS).
+fn deck_path(file_path: &FilePath) -> Option<PathBuf> {
+    match file_path {
+        FilePath::Path(pb) if pb.extension().is_some_and(|ext| ext == DECK_EXTENSION) => {
+            Some(pb.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Loads the images of a deck, which is extracted to the app's cache,
+/// and lets the frontend know about the deck's settings.
+fn load_deck(
+    app: &AppHandle,
+    deck_path: PathBuf,
+) -> Result<(Option<FilePath>, Vec<FilePath>), String> {
+    let target = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("decks")
+        .join(deck_path.file_stem().unwrap_or_default());
+    let deck = open_deck(&deck_path, &target)?;
+
+    let images = deck
+        .images
+        .into_iter()
+        .map(FilePath::from)
+        .collect::<Vec<_>>();
+    let mut images = filter_to_supported_images(app, &images);
+    if deck.ordered {
+        // Keep the deck's order, but still start a new game.
+        let state = app.state::<Mutex<RevealState>>();
+        let mut state = state.lock().unwrap();
+        state.keep_order = true;
+        state.reseed();
+    } else {
+        shuffle(app, &mut images);
+    }
+
+    app.emit("deck-loaded", deck.settings).unwrap();
+    Ok((Some(FilePath::from(deck_path)), images))
+}
+
+/// Scans the folder as configured in the settings and remembers each file's subfolder.
+fn load_from_folder(app: &AppHandle, folder_path: PathBuf) -> Vec<FilePath> {
     assert!(folder_path.is_dir() && folder_path.exists());
 
-    match std::fs::read_dir(&folder_path) {
-        Ok(entries) => entries
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| entry.path().is_file())
-            .filter_map(|entry| std::fs::canonicalize(entry.path()).ok())
-            .map(FilePath::from)
-            .collect(),
+    let state = app.state::<Mutex<RevealState>>();
+    // Scanning large folders takes a while, hence the lock is not held meanwhile.
+    let folder_scan = state.lock().unwrap().settings.folder_scan.clone();
+    match scan_folder(&folder_path, &folder_scan) {
+        Ok(files) => {
+            state.lock().unwrap().subfolders = files
+                .iter()
+                .map(|file| (file.path.clone(), file.subfolder.clone()))
+                .collect();
+            files
+                .into_iter()
+                .map(|file| FilePath::from(file.path))
+                .collect()
+        }
         Err(e) => {
-            log::error!("Couldn't load from path '{:?}': {:?}", folder_path, e);
+            log::error!("Couldn't load from path '{:?}': {}", folder_path, e);
             Vec::new()
         }
     }
@@ -317,20 +399,31 @@ fn load_from_folder(folder_path: PathBuf) -> Vec<FilePath> {
 
 #[allow(unused_variables)]
 fn filter_to_supported_images(app: &AppHandle, file_paths: &[FilePath]) -> Vec<FilePath> {
-    file_paths
+    // Images that could be shown by a build with more features.
+    let mut skipped = 0;
+    let mut skip = |extension: &str| {
+        if OPTIONAL_IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+            skipped += 1;
+        }
+        false
+    };
+
+    let images: Vec<FilePath> = file_paths
         .iter()
         .filter(|fp| match fp {
             FilePath::Path(path_buf) => path_buf.is_file() && path_buf.exists(),
             FilePath::Url(_url) => true, // TODO ...
         })
         .filter(|fp| match fp {
-            FilePath::Path(path_buf) => path_buf
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| SUPPORTED_IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-                .unwrap_or(false),
+            // The extension may be missing or wrong, hence look at the content.
+            FilePath::Path(path_buf) => match read_header(path_buf) {
+                Ok(header) if detect_image_type(&header).is_some() => true,
+                Ok(header) => infer::get(&header).is_some_and(|kind| skip(kind.extension())),
+                Err(_) => false,
+            },
             FilePath::Url(url) => {
                 log::debug!("Checking extension of {:?}", url);
+                let extension;
                 #[cfg(target_os = "android")]
                 {
                     // Android content urls start with 'content://'
@@ -341,30 +434,108 @@ fn filter_to_supported_images(app: &AppHandle, file_paths: &[FilePath]) -> Vec<F
                         value: Some(url.to_string()),
                     });
                     log::debug!("Response from android mime request: {:?}", response);
-                    match response {
+                    extension = match response {
                         Ok(MimeRequestResponse {
                             value: Some(mime_type),
-                        }) => SUPPORTED_IMAGE_EXTENSIONS.contains(&mime_type.as_str()),
-                        _ => false,
-                    }
+                        }) => Some(mime_type),
+                        _ => None,
+                    };
                 }
                 #[cfg(not(target_os = "android"))]
                 {
                     // iOS urls start with file:// and contain the file extension
-                    url.as_str()
+                    extension = url
+                        .as_str()
                         .rfind('.')
-                        .map(|pos| url.as_str()[pos + 1..].into())
-                        .map(|ext| SUPPORTED_IMAGE_EXTENSIONS.contains(&ext))
-                        .unwrap_or(false)
+                        .map(|pos| url.as_str()[pos + 1..].to_owned());
                 }
+                extension.is_some_and(|ext| is_supported_extension(&ext) || skip(&ext))
             }
         })
         .map(ToOwned::to_owned)
-        .collect()
+        .collect();
+
+    if skipped > 0 {
+        app.dialog()
+            .message(format!(
+                "{skipped} HEIC or AVIF image(s) are skipped, \
+                this version of the app is not able to show them."
+            ))
+            .kind(MessageDialogKind::Warning)
+            .title("Images skipped.")
+            .show(|_| {});
+    }
+    images
+}
+
+/// Whether images with this extension are supported, either as they are or after transcoding.
+fn is_supported_extension(extension: &str) -> bool {
+    let extension = extension.to_lowercase();
+    SUPPORTED_IMAGE_EXTENSIONS.contains(&extension.as_str())
+        || TRANSCODED_IMAGE_EXTENSIONS.contains(&extension.as_str())
+}
+
+fn read_header(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)?
+        .take(HEADER_SIZE)
+        .read_to_end(&mut header)?;
+    Ok(header)
 }
 
-fn shuffle(image_paths: &mut [FilePath]) {
-    image_paths.shuffle(&mut thread_rng());
+/// Detects the type of a supported image from its content, as MIME subtype, e.g. 'jpeg'.
+/// Anything else, including unsupported images, yields `None`.
+fn detect_image_type(bytes: &[u8]) -> Option<String> {
+    match infer::get(bytes) {
+        Some(kind) if kind.matcher_type() == infer::MatcherType::Image => {
+            is_supported_extension(kind.extension())
+                .then(|| kind.mime_type().trim_start_matches("image/").to_owned())
+        }
+        // SVGs are text, 'infer' at most recognizes them as XML.
+        _ if SUPPORTED_IMAGE_EXTENSIONS.contains(&"svg") && is_svg(bytes) => Some("svg+xml".into()),
+        _ => None,
+    }
+}
+
+/// Whether the root element is `<svg`, skipping only the XML prolog, doctype, comments and whitespace.
+fn is_svg(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(HEADER_SIZE as usize)];
+    let head = String::from_utf8_lossy(head);
+    let mut rest = head.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        let end = if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<!") {
+            // The doctype may declare entities within brackets.
+            match (rest.find('['), rest.find('>')) {
+                (Some(bracket), Some(end)) if bracket < end => rest
+                    .find(']')
+                    .and_then(|close| rest[close..].find('>').map(|end| close + end + 1)),
+                (_, end) => end.map(|end| end + 1),
+            }
+        } else {
+            return rest.strip_prefix("<svg").is_some_and(|rest| {
+                rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            });
+        };
+        match end {
+            Some(end) => rest = &rest[end..],
+            None => return false,
+        }
+    }
+}
+
+/// Every newly loaded set of images starts a new game, hence the
+/// random number generator is restarted before shuffling.
+fn shuffle(app: &AppHandle, image_paths: &mut [FilePath]) {
+    let state = app.state::<Mutex<RevealState>>();
+    let mut state = state.lock().unwrap();
+    state.keep_order = false;
+    state.reseed();
+    shuffle_images(image_paths, &mut state.rng);
 }
 
 pub fn get_image(
@@ -372,58 +543,98 @@ pub fn get_image(
     app: &AppHandle,
     state: &State<'_, Mutex<RevealState>>,
 ) -> Result<ImageWithMeta, String> {
-    let mut state = state.lock().unwrap();
-
-    if state.images.is_empty() {
-        return Ok(example());
-    }
+    // Decoding and transcoding can take a while, hence the lock is not held meanwhile.
+    let (folder_label, file_label, image_path, max_resolution, subfolder) = {
+        let mut state = state.lock().unwrap();
 
-    let new_index = (state.image_index as isize + update_index)
-        .rem_euclid(state.images.len() as isize) as usize;
-    log::debug!(
-        "Updating index {} by {update_index}. New {}.",
-        state.image_index,
-        new_index
-    );
-    state.image_index = new_index;
-
-    app.emit("image-index", (new_index, state.images.len()))
-        .unwrap();
+        if state.images.is_empty() {
+            return Ok(example(&mut state.rng));
+        }
 
-    let image_path = &state.images[state.image_index];
+        let new_index = (state.image_index as isize + update_index)
+            .rem_euclid(state.images.len() as isize) as usize;
+        log::debug!(
+            "Updating index {} by {update_index}. New {}.",
+            state.image_index,
+            new_index
+        );
+        state.image_index = new_index;
+
+        app.emit("image-index", (new_index, state.images.len()))
+            .unwrap();
+
+        let image_path = state.images[state.image_index].clone();
+        let max_resolution = state
+            .settings
+            .max_resolution
+            .or_else(|| largest_monitor_size(app));
+        let subfolder = match &image_path {
+            FilePath::Path(pb) => state.subfolders.get(pb).cloned(),
+            FilePath::Url(_url) => None,
+        };
+        (
+            folder_label(&image_path, &state.subfolders),
+            file_label(&image_path, &state.images),
+            image_path,
+            max_resolution,
+            subfolder,
+        )
+    };
 
-    match image_path {
+    match &image_path {
         FilePath::Path(pb) => std::fs::read(pb),
         FilePath::Url(_url) => app.fs().read(image_path.clone()),
     }
-    .map(|bytes| (general_purpose::STANDARD.encode(&bytes), read_exif(&bytes)))
-    .map(|(base64, exif)| ImageWithMeta {
-        base64,
-        date_taken: match exif {
-            // TODO only do this if needed
-            Ok(date_taken) => Some(date_taken),
-            Err(msg) => {
-                log::debug!("Could not load exif: {}", msg);
-                None
-            }
-        },
-        image_type: match image_path {
-            FilePath::Path(pb) => Ok(pb.clone()),
-            FilePath::Url(_url) => image_path.clone().into_path(), // TODO error handling should happen here?!
-        }
-        .map_err(|e| e.to_string())
-        .and_then(|pb| pb.extension().map(|s| s.to_owned()).ok_or("err".into()))
-        .and_then(|s| s.to_str().map(|s| s.to_owned()).ok_or("err2".into()))
-        .map(|image_type| {
-            match image_type.as_str() {
-                "jpg" => "jpeg".into(), // IANA only knows jpeg, not jpg
-                "svg" => "svg+xml".into(),
-                _ => image_type,
-            }
+    .map_err(|e| e.to_string())
+    .and_then(|bytes| {
+        let image_type = detect_image_type(&bytes)
+            .ok_or(format!("'{}' is not a supported image.", image_path))?;
+        // TODO only do this if needed
+        let exif = read_exif(&bytes);
+        // The webview may or may not respect the orientation, hence the pixels are turned instead.
+        let orientation = exif
+            .as_ref()
+            .ok()
+            .and_then(orientation)
+            .unwrap_or(Orientation::NoTransforms);
+        let (bytes, image_type) = if TRANSCODED_IMAGE_EXTENSIONS.contains(&image_type.as_str())
+            || orientation != Orientation::NoTransforms
+            || exceeds_resolution(&bytes, &image_type, max_resolution)
+        {
+            transcode(&bytes, &image_type, orientation, max_resolution)
+                .map_err(|e| format!("Couldn't transcode '{}': {}", image_path, e))?
+        } else {
+            (bytes, image_type)
+        };
+        let sidecar = read_sidecar(&image_path).unwrap_or_else(|msg| {
+            log::warn!("{}", msg);
+            Sidecar::default()
+        });
+        Ok(ImageWithMeta {
+            base64: general_purpose::STANDARD.encode(&bytes),
+            date_taken: match exif.as_ref().map_err(String::clone).and_then(date_taken) {
+                Ok(date_taken) => Some(date_taken),
+                Err(msg) => {
+                    log::debug!("Could not load exif: {}", msg);
+                    None
+                }
+            },
+            location: match exif.as_ref().map_err(String::clone).and_then(gps_location) {
+                Ok(location) => Some(location),
+                Err(msg) => {
+                    log::debug!("Could not load location: {}", msg);
+                    None
+                }
+            },
+            image_type,
+            folder_label,
+            file_label,
+            key_regions: sidecar.key_regions,
+            questions: sidecar.questions,
+            subfolder,
+            bytes,
         })
-        .unwrap_or("png".into()), // Browsers are somewhat forgiving, try with png
     })
-    .map_err(|e| e.to_string())
 }
 
 const EXAMPLES: [(&[u8], &str); 5] = [
@@ -435,33 +646,75 @@ const EXAMPLES: [(&[u8], &str); 5] = [
 ];
 
 /// Returns a randomly selected exemplary image.
-pub fn example() -> ImageWithMeta {
-    let mut rng = rand::thread_rng();
+pub fn example(rng: &mut impl Rng) -> ImageWithMeta {
     let supported_examples = EXAMPLES
         .iter()
         .filter(|(_, ext)| SUPPORTED_IMAGE_EXTENSIONS.contains(ext))
         .collect::<Vec<_>>();
     let selected = supported_examples[rng.gen_range(0..supported_examples.len())];
     ImageWithMeta {
+        bytes: selected.0.to_vec(),
         base64: general_purpose::STANDARD.encode(selected.0),
-        image_type: selected.1.into(),
+        image_type: detect_image_type(selected.0).unwrap_or(selected.1.into()),
         date_taken: None,
+        location: None,
+        folder_label: None,
+        file_label: None,
+        key_regions: Vec::new(),
+        questions: Vec::new(),
+        subfolder: None,
     }
 }
 
-fn read_exif(bytes: &[u8]) -> Result<NaiveDateTime, String> {
+fn read_exif(bytes: &[u8]) -> Result<Exif, String> {
     let mut bufreader = std::io::Cursor::new(bytes);
     Reader::new()
         .read_from_container(&mut bufreader)
         .map_err(|e| e.to_string())
-        .and_then(|data| {
-            data.get_field(Tag::DateTimeOriginal, In::PRIMARY)
-                .ok_or("DateTimeOriginal not included".into())
-                .map(|field| field.display_value().to_string())
-        })
+}
+
+fn date_taken(exif: &Exif) -> Result<NaiveDateTime, String> {
+    exif.get_field(Tag::DateTimeOriginal, In::PRIMARY)
+        .ok_or("DateTimeOriginal not included".into())
+        .map(|field| field.display_value().to_string())
         .and_then(|s| {
             log::debug!("Exif str: {}", s);
             NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
                 .map_err(|e| format!("{} ({})", e, s))
         })
 }
+
+fn orientation(exif: &Exif) -> Option<Orientation> {
+    exif.get_field(Tag::Orientation, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .and_then(|value| u8::try_from(value).ok())
+        .and_then(Orientation::from_exif)
+}
+
+fn gps_location(exif: &Exif) -> Result<GpsLocation, String> {
+    Ok(GpsLocation {
+        latitude: gps_coordinate(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef, b'S')?,
+        longitude: gps_coordinate(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef, b'W')?,
+    })
+}
+
+/// Converts degrees, minutes and seconds to decimal degrees,
+/// which are negative for the southern and western hemisphere.
+fn gps_coordinate(exif: &Exif, tag: Tag, ref_tag: Tag, negative_ref: u8) -> Result<f64, String> {
+    let degrees = match exif.get_field(tag, In::PRIMARY).map(|field| &field.value) {
+        Some(Value::Rational(dms)) if dms.len() == 3 => {
+            dms[0].to_f64() + dms[1].to_f64() / 60.0 + dms[2].to_f64() / 3600.0
+        }
+        _ => return Err(format!("{} not included", tag)),
+    };
+    match exif
+        .get_field(ref_tag, In::PRIMARY)
+        .map(|field| &field.value)
+    {
+        Some(Value::Ascii(refs)) if refs.first().and_then(|r| r.first()) == Some(&negative_ref) => {
+            Ok(-degrees)
+        }
+        Some(Value::Ascii(_)) => Ok(degrees),
+        _ => Err(format!("{} not included", ref_tag)),
+    }
+}
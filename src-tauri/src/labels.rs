@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri_plugin_fs::FilePath;
+
+use crate::common::Label;
+
+/// Labels an image by the subfolder of the scanned folder containing it, e.g. 'cat' for
+/// `cat/1.jpg` within `animals`, with the other scanned subfolders, e.g. 'dog', as alternatives.
+/// Images directly within the scanned folder, or not scanned at all, are not labeled.
+pub fn folder_label(
+    image_path: &FilePath,
+    subfolders: &HashMap<PathBuf, PathBuf>,
+) -> Option<Label> {
+    let FilePath::Path(image_path) = image_path else {
+        return None;
+    };
+    let name = folder_name(subfolders.get(image_path)?)?;
+
+    let mut siblings = subfolders
+        .values()
+        .filter_map(|subfolder| folder_name(subfolder))
+        .filter(|sibling| *sibling != name)
+        .collect::<Vec<_>>();
+    siblings.sort();
+    siblings.dedup();
+
+    Some(Label { name, siblings })
+}
+
+fn folder_name(subfolder: &Path) -> Option<String> {
+    subfolder.file_name()?.to_str().map(ToOwned::to_owned)
+}
+
+/// Labels an image by its file name without numbering, e.g. 'red panda' for
+/// `red_panda_03.jpg`, with the labels of the other images as alternatives.
+pub fn file_label(image_path: &FilePath, images: &[FilePath]) -> Option<Label> {
+    let name = label_from_file_name(image_path)?;
+
+    let mut siblings = images
+        .iter()
+        .filter_map(label_from_file_name)
+        .filter(|sibling| *sibling != name)
+        .collect::<Vec<_>>();
+    siblings.sort();
+    siblings.dedup();
+
+    Some(Label { name, siblings })
+}
+
+fn label_from_file_name(image_path: &FilePath) -> Option<String> {
+    let FilePath::Path(image_path) = image_path else {
+        return None;
+    };
+    let label = image_path
+        .file_stem()?
+        .to_str()?
+        .trim_end_matches(|c: char| c.is_ascii_digit() || "_- ()".contains(c))
+        .replace(['_', '-'], " ");
+
+    (!label.is_empty()).then_some(label)
+}
@@ -1,17 +1,33 @@
 mod common;
+mod deck;
+mod folder_scan;
+mod frames;
+mod gazetteer;
 mod image_loading;
 #[cfg(target_os = "ios")]
 mod ios;
+mod labels;
 mod plane_covering;
 mod questions;
 #[cfg(target_os = "android")]
 mod reveal_plugin_android;
+mod saliency;
+mod scramble;
+mod sidecar;
+mod transcoding;
+mod uncovering;
 mod utils;
 
-use common::{Polygon, RevealObject, RevealSettings, RevealState};
-use questions::simple_year_question;
+use common::{
+    FolderScan, Point, PointSampling, Polygon, QuestionType, RevealFrames, RevealObject,
+    RevealSettings, RevealState, Scramble, UncoveringOrder, UncoveringStrategy, MAX_SEED,
+};
+use frames::FrameMode;
+use questions::pick_question;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use saliency::SaliencyMap;
 use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Emitter;
@@ -24,15 +40,120 @@ fn get_settings(state: tauri::State<'_, Mutex<RevealState>>) -> RevealSettings {
     state.lock().unwrap().settings.clone()
 }
 
+/// Starts a new game with the loaded images, shuffled according to the seed.
+#[tauri::command]
+fn set_seed(seed: Option<u64>, state: tauri::State<'_, Mutex<RevealState>>) -> Result<(), String> {
+    if seed.is_some_and(|seed| seed > MAX_SEED) {
+        return Err(format!("Seeds must not be larger than {MAX_SEED}."));
+    }
+    let mut state = state.lock().unwrap();
+    state.settings.seed = seed;
+    state.reshuffle();
+    Ok(())
+}
+
+#[tauri::command]
+fn set_uncovering_strategy(
+    strategy: UncoveringStrategy,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<(), String> {
+    if let UncoveringStrategy::Timed { interval_ms: 0 } = strategy {
+        return Err("The uncovering interval must be longer than 0 seconds.".into());
+    }
+    let mut state = state.lock().unwrap();
+    state.settings.uncovering_strategy = strategy;
+    state.uncovering.speed = 1.0;
+    Ok(())
+}
+
+/// Takes effect the next time a folder is scanned.
+#[tauri::command]
+fn set_folder_scan(
+    scan: FolderScan,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<(), String> {
+    folder_scan::compile_patterns(&scan.include)?;
+    folder_scan::compile_patterns(&scan.exclude)?;
+    state.lock().unwrap().settings.folder_scan = scan;
+    Ok(())
+}
+
+/// Takes effect with the next image.
+#[tauri::command]
+fn set_max_resolution(
+    resolution: Option<u32>,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<(), String> {
+    if resolution == Some(0) {
+        return Err("Invalid maximum resolution 0.".into());
+    }
+    state.lock().unwrap().settings.max_resolution = resolution;
+    Ok(())
+}
+
+/// Takes effect with the next covering.
+#[tauri::command]
+fn set_point_sampling(
+    sampling: PointSampling,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<(), String> {
+    if let PointSampling::PoissonDisk {
+        min_distance: Some(d),
+    } = sampling
+    {
+        if !(0.01..=1.0).contains(&d) {
+            return Err("The minimum point distance must be between 0.01 and 1.".into());
+        }
+    }
+    state.lock().unwrap().settings.point_sampling = sampling;
+    Ok(())
+}
+
+/// Takes effect with the next covering.
+#[tauri::command]
+fn set_uncovering_order(order: UncoveringOrder, state: tauri::State<'_, Mutex<RevealState>>) {
+    state.lock().unwrap().settings.uncovering_order = order;
+}
+
+#[tauri::command]
+fn restart_uncovering(state: tauri::State<'_, Mutex<RevealState>>) {
+    let mut state = state.lock().unwrap();
+    let total = state.uncovering.total;
+    state.uncovering.restart(total);
+}
+
+#[tauri::command]
+fn pause_uncovering(state: tauri::State<'_, Mutex<RevealState>>) {
+    state.lock().unwrap().uncovering.pause();
+}
+
+#[tauri::command]
+fn resume_uncovering(state: tauri::State<'_, Mutex<RevealState>>) {
+    state.lock().unwrap().uncovering.resume();
+}
+
+#[tauri::command]
+fn speed_up_uncovering(
+    factor: f64,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<f64, String> {
+    if !factor.is_finite() || factor <= 0.0 {
+        return Err(format!("Invalid speed-up factor {factor}."));
+    }
+    Ok(state.lock().unwrap().uncovering.speed_up(factor))
+}
+
 #[tauri::command]
 fn debug_infos(app_handle: AppHandle) -> String {
     utils::debug_info(&app_handle)
 }
 
-#[tauri::command]
+// Decoding and transcoding large images takes a while, hence not on the main thread.
+#[tauri::command(async)]
 fn get_image(
     u: isize,
-    quiz_year: bool,
+    question_types: Vec<QuestionType>,
+    zoom_steps: Option<usize>,
     app_handle: AppHandle,
     state: tauri::State<'_, Mutex<RevealState>>,
 ) -> RevealObject {
@@ -51,31 +172,63 @@ fn get_image(
                 .kind(MessageDialogKind::Warning)
                 .title("Loading image failed.")
                 .show(|_| {});
-            Ok::<_, String>(image_loading::example())
+            Ok::<_, String>(image_loading::example(&mut state.lock().unwrap().rng))
         })
         .map(|image_and_meta| {
+            let qna = (!question_types.is_empty()).then(|| {
+                log::debug!(
+                    "Quiz requested: {}, {:?}",
+                    image_and_meta
+                        .date_taken
+                        .map(|dt| dt.to_string())
+                        .unwrap_or("Unknown date".into()),
+                    image_and_meta.location
+                );
+                pick_question(
+                    &question_types,
+                    &image_and_meta,
+                    &mut state.lock().unwrap().rng,
+                )
+                .unwrap_or_default()
+            });
+            let crops = {
+                let mut state = state.lock().unwrap();
+                state.current_image = image_and_meta.bytes;
+                state.key_regions = image_and_meta.key_regions;
+                match zoom_steps {
+                    Some(steps) => {
+                        let state = &mut *state;
+                        let crops = frames::zoom_crops(steps, &state.key_regions, &mut state.rng);
+                        state.uncovering.restart(crops.len());
+                        crops
+                    }
+                    None => Vec::new(),
+                }
+            };
+            // The subfolder would give away the answer to a folder question.
+            let asks_for_folder = qna
+                .as_ref()
+                .is_some_and(|qna| matches!(qna.question_type, Some(QuestionType::Folder)));
             let mut reveal_object = RevealObject {
                 image: image_and_meta.base64,
                 image_type: image_and_meta.image_type,
                 question: None,
                 answers: Vec::new(),
                 correct_answer: 0,
+                hints: Vec::new(),
+                explanation: None,
+                subfolder: image_and_meta
+                    .subfolder
+                    .filter(|subfolder| !asks_for_folder && !subfolder.as_os_str().is_empty())
+                    .map(|subfolder| subfolder.to_string_lossy().into_owned()),
+                crops,
             };
-            if quiz_year {
-                log::debug!(
-                    "Quiz year requested: {}",
-                    image_and_meta
-                        .date_taken
-                        .map(|dt| dt.to_string())
-                        .unwrap_or("Unknown date".into())
-                );
-                let qna = image_and_meta
-                    .date_taken
-                    .map(|dt| simple_year_question(&dt))
-                    .unwrap_or_default();
+            if let Some(qna) = qna {
                 reveal_object.question = Some(qna.question);
                 reveal_object.answers.extend(qna.answers);
                 reveal_object.correct_answer = qna.idx_correct;
+                reveal_object.hints = qna.hints;
+                reveal_object.explanation = qna.explanation;
             }
             reveal_object
         })
@@ -163,22 +316,111 @@ fn get_image_paths(force_selection: bool, folder: bool, verbose: bool, app: AppH
     "ok".to_string()
 }
 
-#[tauri::command]
+// Estimating the saliency decodes the image, hence not on the main thread.
+#[tauri::command(async)]
 fn load_covering(
     width: f64,
     height: f64,
     n: usize,
     object_type: String,
+    focus: Option<Point>,
+    seed: Option<u64>,
+    state: tauri::State<'_, Mutex<RevealState>>,
 ) -> Result<Vec<Polygon>, String> {
+    let sampling = state.lock().unwrap().settings.point_sampling.clone();
+
+    // An explicit seed yields the same covering regardless of the game's progress.
+    let seed = seed.unwrap_or_else(|| state.lock().unwrap().rng.gen());
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let focus = focus.unwrap_or(Point {
+        x: width / 2.0,
+        y: height / 2.0,
+    });
     let mut covering = match object_type.as_str() {
         "Rectangles" => plane_covering::cover_rectangles(n, width, height),
-        _ => plane_covering::cover_triangles(n, width, height),
+        "UnevenRectangles" => plane_covering::cover_rectangles_uneven(n, width, height, &mut rng),
+        "Rings" => plane_covering::cover_rings(n, width, height, &focus, &mut rng),
+        "Jigsaw" => plane_covering::cover_jigsaw(n, width, height, &mut rng),
+        "Quadtree" => plane_covering::cover_quadtree(n, width, height, &mut rng),
+        "Voronoi" => plane_covering::cover_voronoi(n, width, height, &sampling, &mut rng),
+        "Hexagons" => plane_covering::cover_hexagons(n, width, height),
+        _ => plane_covering::cover_triangles(n, width, height, &sampling, &mut rng),
+    };
+
+    // Shuffle in any case, such that equally ranked polygons appear in random order.
+    covering.shuffle(&mut rng);
+    let order = state.lock().unwrap().settings.uncovering_order.clone();
+    let covering = match order {
+        UncoveringOrder::Saliency => {
+            let bytes = state.lock().unwrap().current_image.clone();
+            match SaliencyMap::from_image(&bytes) {
+                Ok(map) => saliency::order_by_saliency(covering, &map, width, height),
+                Err(e) => {
+                    // E.g. vector graphics, keep the random order then.
+                    log::debug!("Could not estimate saliency: {}", e);
+                    covering
+                }
+            }
+        }
+        _ => plane_covering::order_covering(covering, &order, &focus),
     };
+    // Key regions take precedence over any order.
+    let covering = plane_covering::delay_key_regions(
+        covering,
+        &state.lock().unwrap().key_regions,
+        width,
+        height,
+    );
 
-    covering.shuffle(&mut thread_rng());
+    state.lock().unwrap().uncovering.restart(covering.len());
     Ok(covering)
 }
 
+// Blurring and encoding the frames takes a while, hence not on the main thread.
+#[tauri::command(async)]
+fn load_frames(
+    n: usize,
+    object_type: String,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Result<RevealFrames, String> {
+    let mode = match object_type.as_str() {
+        "Pixelation" => FrameMode::Pixelation,
+        "Blur" => FrameMode::Blur,
+        _ => return Err(format!("'{object_type}' is not a frame-based reveal mode.")),
+    };
+
+    let bytes = state.lock().unwrap().current_image.clone();
+    let frames = frames::render_frames(&bytes, n, &mode)?;
+
+    state
+        .lock()
+        .unwrap()
+        .uncovering
+        .restart(frames.frames.len());
+    Ok(frames)
+}
+
+#[tauri::command]
+fn load_scramble(
+    width: f64,
+    height: f64,
+    n: usize,
+    seed: Option<u64>,
+    state: tauri::State<'_, Mutex<RevealState>>,
+) -> Scramble {
+    let seed = seed.unwrap_or_else(|| state.lock().unwrap().rng.gen());
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let scramble = scramble::scramble_tiles(n, width, height, &mut rng);
+    state
+        .lock()
+        .unwrap()
+        .uncovering
+        .restart(scramble.swaps.len());
+    scramble
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     #[allow(unused_mut)]
@@ -210,12 +452,26 @@ pub fn run() {
                 ios::mark_home_dir(&handle);
             }
 
+            uncovering::spawn_ticker(app.handle().to_owned());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             debug_infos,
             get_settings,
+            set_seed,
+            set_uncovering_strategy,
+            set_folder_scan,
+            set_max_resolution,
+            set_point_sampling,
+            set_uncovering_order,
+            restart_uncovering,
+            pause_uncovering,
+            resume_uncovering,
+            speed_up_uncovering,
             load_covering,
+            load_frames,
+            load_scramble,
             get_image,
             get_image_paths,
         ])
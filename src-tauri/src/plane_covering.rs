@@ -1,43 +1,563 @@
-use crate::common::{Point, Polygon};
+use crate::common::{KeyRegion, Point, PointSampling, Polygon, UncoveringOrder};
 use delaunator::triangulate;
 use rand::Rng;
 
+/// Tiles whose sides differ by more than this factor are considered too narrow.
+const MAX_TILE_ASPECT_RATIO: f64 = 2.0;
+
+/// Covers the plane with a grid of equally sized rectangles. Rows and columns
+/// are chosen such that the number of rectangles is as close as possible to `n`
+/// while the rectangles remain roughly square, whatever the plane's aspect ratio.
 pub fn cover_rectangles(n: usize, w: f64, h: f64) -> Vec<Polygon> {
-    let rows_cols = n.isqrt();
-    let row_step: f64 = h / rows_cols as f64;
-    let col_step: f64 = w / rows_cols as f64;
-    itertools::iproduct!(0..rows_cols, 0..rows_cols)
-        .map(|(row, col)| Polygon {
-            pnts: vec![
-                Point {
-                    x: (col as f64) * col_step,
-                    y: (row as f64) * row_step,
-                },
-                Point {
-                    x: (col as f64) * col_step + col_step,
-                    y: (row as f64) * row_step,
-                },
-                Point {
-                    x: (col as f64) * col_step + col_step,
-                    y: (row as f64) * row_step + row_step,
-                },
-                Point {
-                    x: (col as f64) * col_step,
-                    y: (row as f64) * row_step + row_step,
-                },
-            ],
+    let (rows, cols) = grid_dimensions(n, w, h);
+    log::debug!("Using {rows} rows and {cols} cols.");
+
+    let row_step: f64 = h / rows as f64;
+    let col_step: f64 = w / cols as f64;
+    itertools::iproduct!(0..rows, 0..cols)
+        .map(|(row, col)| {
+            rectangle(
+                (col as f64) * col_step,
+                (row as f64) * row_step,
+                col_step,
+                row_step,
+            )
         })
         .collect()
 }
 
+/// Covers the plane with exactly `n` rectangles of uneven size.
+/// The number of rows is derived from the aspect ratio, the rectangles
+/// are distributed across the rows, and all cuts are randomly shifted.
+pub fn cover_rectangles_uneven(n: usize, w: f64, h: f64, rng: &mut impl Rng) -> Vec<Polygon> {
+    let n = n.max(1);
+    let rows = ((n as f64 * h / w).sqrt().round() as usize).clamp(1, n);
+    log::debug!("Using {rows} rows for {n} rectangles.");
+
+    let row_cuts = jittered_cuts(rows, h, rng);
+    let mut rectangles = Vec::with_capacity(n);
+    for (row, ys) in row_cuts.windows(2).enumerate() {
+        // The first rows take the remainder.
+        let cols = n / rows + usize::from(row < n % rows);
+        let col_cuts = jittered_cuts(cols, w, rng);
+        rectangles.extend(
+            col_cuts
+                .windows(2)
+                .map(|xs| rectangle(xs[0], ys[0], xs[1] - xs[0], ys[1] - ys[0])),
+        );
+    }
+
+    rectangles
+}
+
+/// Covers the plane with rectangles of mixed size by repeatedly splitting
+/// a randomly selected rectangle into quadrants until there are about `n`.
+pub fn cover_quadtree(n: usize, w: f64, h: f64, rng: &mut impl Rng) -> Vec<Polygon> {
+    // Don't let single rectangles become tiny, compared to a uniform split.
+    let max_depth = (n.max(1) as f64).log(4.0).ceil() as usize + 2;
+
+    // (x, y, w, h, depth)
+    let mut quads = vec![(0.0, 0.0, w, h, 0)];
+    // Each split adds three rectangles, stop when that overshoots `n` more than it helps.
+    while 2 * quads.len() + 3 < 2 * n {
+        let splittable = quads
+            .iter()
+            .enumerate()
+            .filter(|(_, quad)| quad.4 < max_depth)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        if splittable.is_empty() {
+            break;
+        }
+
+        let (x, y, w, h, depth) = quads.swap_remove(splittable[rng.gen_range(0..splittable.len())]);
+        let (half_w, half_h) = (w / 2.0, h / 2.0);
+        quads.extend([
+            (x, y, half_w, half_h, depth + 1),
+            (x + half_w, y, half_w, half_h, depth + 1),
+            (x, y + half_h, half_w, half_h, depth + 1),
+            (x + half_w, y + half_h, half_w, half_h, depth + 1),
+        ]);
+    }
+
+    log::debug!("Created {} quadtree rectangles.", quads.len());
+
+    quads
+        .into_iter()
+        .map(|(x, y, w, h, _)| rectangle(x, y, w, h))
+        .collect()
+}
+
+/// Covers the plane with classic jigsaw puzzle pieces. The pieces follow the
+/// grid of `cover_rectangles`, with inner edges replaced by tabs and blanks.
+pub fn cover_jigsaw(n: usize, w: f64, h: f64, rng: &mut impl Rng) -> Vec<Polygon> {
+    let (rows, cols) = grid_dimensions(n, w, h);
+    let corner = |row: usize, col: usize| Point {
+        x: col as f64 * w / cols as f64,
+        y: row as f64 * h / rows as f64,
+    };
+
+    // Shared edges are created once, such that neighboring pieces fit exactly.
+    // Horizontal edges run from left to right, vertical edges from top to bottom.
+    let mut horizontal = Vec::with_capacity(rows + 1);
+    for row in 0..=rows {
+        let edges: Vec<_> = (0..cols)
+            .map(|col| {
+                let (from, to) = (corner(row, col), corner(row, col + 1));
+                if row == 0 || row == rows {
+                    vec![from, to]
+                } else {
+                    jigsaw_edge(&from, &to, rng)
+                }
+            })
+            .collect();
+        horizontal.push(edges);
+    }
+    let mut vertical = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let edges: Vec<_> = (0..=cols)
+            .map(|col| {
+                let (from, to) = (corner(row, col), corner(row + 1, col));
+                if col == 0 || col == cols {
+                    vec![from, to]
+                } else {
+                    jigsaw_edge(&from, &to, rng)
+                }
+            })
+            .collect();
+        vertical.push(edges);
+    }
+
+    itertools::iproduct!(0..rows, 0..cols)
+        .map(|(row, col)| {
+            // Walk around the piece clockwise, leaving out each edge's last point
+            // since it is the first point of the next edge.
+            let top = &horizontal[row][col];
+            let right = &vertical[row][col + 1];
+            let bottom = &horizontal[row + 1][col];
+            let left = &vertical[row][col];
+            let pnts = top[..top.len() - 1]
+                .iter()
+                .chain(&right[..right.len() - 1])
+                .chain(bottom[1..].iter().rev())
+                .chain(left[1..].iter().rev())
+                .cloned()
+                .collect();
+            Polygon { pnts }
+        })
+        .collect()
+}
+
+/// Size of a tab's head, relative to the length of its edge.
+const JIGSAW_TAB_RADIUS: f64 = 0.12;
+/// Half the width of a tab's neck, relative to the length of its edge.
+const JIGSAW_NECK_WIDTH: f64 = 0.08;
+/// Number of segments approximating the round head of a tab.
+const JIGSAW_TAB_SEGMENTS: usize = 16;
+
+/// Approximates an edge between two puzzle pieces, including both end points.
+/// The tab bulges to a random side of the edge and is slightly off center.
+fn jigsaw_edge(from: &Point, to: &Point, rng: &mut impl Rng) -> Vec<Point> {
+    let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let center = rng.gen_range(0.45..0.55);
+
+    // Work in coordinates along (u) and perpendicular to (v) the edge,
+    // both relative to its length, and transform back at the end.
+    let to_plane = |u: f64, v: f64| Point {
+        x: from.x + u * (to.x - from.x) - side * v * (to.y - from.y),
+        y: from.y + u * (to.y - from.y) + side * v * (to.x - from.x),
+    };
+
+    // The head is a circle passing through both ends of the neck.
+    let head = (JIGSAW_TAB_RADIUS.powi(2) - JIGSAW_NECK_WIDTH.powi(2)).sqrt();
+    let start = (-head)
+        .atan2(-JIGSAW_NECK_WIDTH)
+        .rem_euclid(std::f64::consts::TAU);
+    let end = (-head).atan2(JIGSAW_NECK_WIDTH);
+
+    let mut pnts = vec![from.clone()];
+    pnts.extend((0..=JIGSAW_TAB_SEGMENTS).map(|i| {
+        let angle = start - (start - end) * i as f64 / JIGSAW_TAB_SEGMENTS as f64;
+        to_plane(
+            center + JIGSAW_TAB_RADIUS * angle.cos(),
+            head + JIGSAW_TAB_RADIUS * angle.sin(),
+        )
+    }));
+    pnts.push(to.clone());
+
+    pnts
+}
+
+/// Number of steps used to approximate the arcs of rings.
+/// Sectors start and end on these steps, such that neighboring rings share their vertices.
+const RING_ARC_STEPS: usize = 360;
+
+/// Rings split into fewer sectors would hardly look like rings anymore.
+const MIN_RING_SECTORS: usize = 3;
+
+/// Covers the plane with concentric rings around `focus`, like a dartboard.
+/// The innermost ring is a single disk, all other rings are split into sectors.
+/// The number of rings is chosen such that pieces about as wide as they are deep
+/// come close to `n`, then the sectors of each ring are adjusted such that
+/// the number of (clipped) pieces is as close as possible to `n`.
+pub fn cover_rings(n: usize, w: f64, h: f64, focus: &Point, rng: &mut impl Rng) -> Vec<Polygon> {
+    let rotation = rng.gen_range(0.0..std::f64::consts::TAU);
+    // The outermost ring has to reach the farthest corner,
+    // even where its arc is approximated by a chord.
+    let radius = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)]
+        .iter()
+        .map(|(x, y)| ((x - focus.x).powi(2) + (y - focus.y).powi(2)).sqrt())
+        .fold(0.0, f64::max)
+        / (std::f64::consts::PI / RING_ARC_STEPS as f64).cos();
+
+    // Pieces at the middle of a ring are about as wide as they are deep.
+    let square_sectors = |ring_count: usize| {
+        (0..ring_count)
+            .map(|ring| {
+                ((std::f64::consts::TAU * (ring as f64 + 0.5)).round() as usize)
+                    .clamp(MIN_RING_SECTORS, RING_ARC_STEPS)
+            })
+            .collect::<Vec<_>>()
+    };
+    let ring = |ring_count: usize, ring: usize, sectors: usize| {
+        let ring_step = radius / ring_count as f64;
+        ring_sectors(ring, sectors, ring_step, rotation, w, h, focus)
+    };
+    let rings = |ring_count: usize, sectors: &[usize]| {
+        (0..ring_count)
+            .map(|r| ring(ring_count, r, sectors[r]))
+            .collect::<Vec<_>>()
+    };
+
+    // The fewest rings with at least `n` square pieces.
+    let mut ring_count = 1;
+    while ring_count < n && piece_count(&rings(ring_count, &square_sectors(ring_count))) < n {
+        ring_count += 1;
+    }
+
+    // Adjust both, the covering with at least and the one with fewer than `n` pieces.
+    let best = (ring_count.saturating_sub(1).max(1)..=ring_count)
+        .map(|ring_count| {
+            let mut sectors = square_sectors(ring_count);
+            let mut covering = rings(ring_count, &sectors);
+            let square_diff = piece_count(&covering).abs_diff(n);
+            adjust_ring_sectors(n, &mut covering, &mut sectors, |r, s| {
+                ring(ring_count, r, s)
+            });
+            (piece_count(&covering).abs_diff(n), square_diff, covering)
+        })
+        .min_by_key(|(diff, square_diff, _)| (*diff, *square_diff))
+        .map(|(_, _, covering)| covering.into_iter().flatten().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    log::debug!("Created {} ring sectors.", best.len());
+
+    best
+}
+
+fn piece_count(rings: &[Vec<Polygon>]) -> usize {
+    rings.iter().map(Vec::len).sum()
+}
+
+/// Splits the widest pieces further, or merges the narrowest ones, one sector at a time,
+/// until there are `n` pieces or no ring can be adjusted anymore.
+fn adjust_ring_sectors(
+    n: usize,
+    rings: &mut [Vec<Polygon>],
+    sectors: &mut [usize],
+    ring_sectors: impl Fn(usize, usize) -> Vec<Polygon>,
+) {
+    let grow = piece_count(rings) < n;
+    while piece_count(rings) != n && (piece_count(rings) < n) == grow {
+        let width = |ring: &usize| sectors[*ring] as f64 / (*ring as f64 + 0.5);
+        let adjustable = (1..rings.len()).filter(|ring| {
+            !rings[*ring].is_empty()
+                && if grow {
+                    sectors[*ring] < RING_ARC_STEPS
+                } else {
+                    sectors[*ring] > MIN_RING_SECTORS
+                }
+        });
+        let ring = if grow {
+            adjustable.min_by(|a, b| width(a).total_cmp(&width(b)))
+        } else {
+            adjustable.max_by(|a, b| width(a).total_cmp(&width(b)))
+        };
+        let Some(ring) = ring else {
+            break;
+        };
+        sectors[ring] = if grow {
+            sectors[ring] + 1
+        } else {
+            sectors[ring] - 1
+        };
+        rings[ring] = ring_sectors(ring, sectors[ring]);
+    }
+}
+
+/// The clipped pieces of a single ring, split into `sectors`.
+/// Ring 0 is the disk in the middle, which is never split.
+fn ring_sectors(
+    ring: usize,
+    sectors: usize,
+    ring_step: f64,
+    rotation: f64,
+    w: f64,
+    h: f64,
+    focus: &Point,
+) -> Vec<Polygon> {
+    let point = |r: f64, step: usize| {
+        let angle = rotation + std::f64::consts::TAU * step as f64 / RING_ARC_STEPS as f64;
+        Point {
+            x: focus.x + r * angle.cos(),
+            y: focus.y + r * angle.sin(),
+        }
+    };
+
+    if ring == 0 {
+        let disk = (0..RING_ARC_STEPS)
+            .map(|step| point(ring_step, step))
+            .collect::<Vec<_>>();
+        return clip_to_bounds(&disk, w, h).into_iter().collect();
+    }
+
+    let (inner, outer) = (ring as f64 * ring_step, (ring + 1) as f64 * ring_step);
+    let boundary = |sector: usize| (sector * RING_ARC_STEPS + sectors / 2) / sectors;
+    (0..sectors)
+        .filter_map(|sector| {
+            let (start, end) = (boundary(sector), boundary(sector + 1));
+            let pnts = (start..=end)
+                .map(|step| point(outer, step))
+                .chain((start..=end).rev().map(|step| point(inner, step)))
+                .collect::<Vec<_>>();
+            clip_to_bounds(&pnts, w, h)
+        })
+        .collect()
+}
+
+/// Sorts the polygons of a covering into the order they are to be uncovered in.
+/// The sort is stable, equally ranked polygons keep their relative order.
+pub fn order_covering(
+    covering: Vec<Polygon>,
+    order: &UncoveringOrder,
+    focus: &Point,
+) -> Vec<Polygon> {
+    if let UncoveringOrder::Random | UncoveringOrder::Saliency = order {
+        return covering;
+    }
+
+    let centroids = covering
+        .iter()
+        .map(|polygon| centroid(&polygon.pnts))
+        .collect::<Vec<_>>();
+    let distance = |c: &Point| ((c.x - focus.x).powi(2) + (c.y - focus.y).powi(2)).sqrt();
+    let max_distance = centroids.iter().map(distance).fold(f64::EPSILON, f64::max);
+    // Enough turns such that the spiral passes most polygons on its way out.
+    let turns = ((covering.len() as f64).sqrt() / 2.0).max(1.0);
+
+    let mut ranked = centroids
+        .iter()
+        .map(|c| match order {
+            UncoveringOrder::Random | UncoveringOrder::Saliency => 0.0,
+            UncoveringOrder::CenterOut => distance(c),
+            UncoveringOrder::EdgesIn => -distance(c),
+            UncoveringOrder::Spiral => {
+                // Position along an archimedean spiral: completed turns plus the angle.
+                let angle = (c.y - focus.y)
+                    .atan2(c.x - focus.x)
+                    .rem_euclid(std::f64::consts::TAU)
+                    / std::f64::consts::TAU;
+                (distance(c) / max_distance * turns - angle).round() + angle
+            }
+            UncoveringOrder::LeftToRight => c.x,
+            UncoveringOrder::TopToBottom => c.y,
+        })
+        .zip(covering)
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(rank_a, _), (rank_b, _)| rank_a.total_cmp(rank_b));
+
+    ranked.into_iter().map(|(_, polygon)| polygon).collect()
+}
+
+/// Reorders a covering such that polygons intersecting a key region
+/// are not uncovered before the region's step, or last if it has none.
+/// Apart from that, the order is kept.
+pub fn delay_key_regions(
+    covering: Vec<Polygon>,
+    regions: &[KeyRegion],
+    w: f64,
+    h: f64,
+) -> Vec<Polygon> {
+    if regions.is_empty() {
+        return covering;
+    }
+    let regions = regions
+        .iter()
+        .map(|region| {
+            let polygon = rectangle(
+                region.x * w,
+                region.y * h,
+                region.width * w,
+                region.height * h,
+            );
+            (polygon, region.not_before)
+        })
+        .collect::<Vec<_>>();
+
+    let mut ordered = Vec::with_capacity(covering.len());
+    let mut delayed = Vec::new();
+    let mut last = Vec::new();
+    for polygon in covering {
+        // The strictest region wins, being uncovered last is the strictest.
+        let steps = regions
+            .iter()
+            .filter(|(region, _)| overlaps_convex(&polygon, region))
+            .map(|(_, not_before)| not_before.unwrap_or(usize::MAX))
+            .max();
+        match steps {
+            None => ordered.push(polygon),
+            Some(usize::MAX) => last.push(polygon),
+            Some(step) => delayed.push((step, polygon)),
+        }
+
+        // Release delayed polygons as soon as their step has been reached.
+        while let Some(idx) = delayed.iter().position(|(step, _)| *step <= ordered.len()) {
+            ordered.push(delayed.remove(idx).1);
+        }
+    }
+    // Polygons whose step has not been reached follow in the order of their steps.
+    delayed.sort_by_key(|(step, _)| *step);
+    ordered.extend(delayed.into_iter().map(|(_, polygon)| polygon));
+    ordered.extend(last);
+
+    ordered
+}
+
+/// Whether a polygon overlaps a convex region. Merely touching borders don't count.
+pub fn overlaps_convex(polygon: &Polygon, region: &Polygon) -> bool {
+    let center = centroid(&region.pnts);
+    let clipped = region
+        .pnts
+        .iter()
+        .zip(region.pnts.iter().cycle().skip(1))
+        .fold(polygon.pnts.clone(), |clipped, (from, to)| {
+            // Keep the side of the edge the region's center lies on.
+            let mut normal = Point {
+                x: to.y - from.y,
+                y: from.x - to.x,
+            };
+            if normal.x * (center.x - from.x) + normal.y * (center.y - from.y) > 0.0 {
+                normal = Point {
+                    x: -normal.x,
+                    y: -normal.y,
+                };
+            }
+            let offset = normal.x * from.x + normal.y * from.y;
+            clip_half_plane(&clipped, &normal, offset)
+        });
+
+    area(&clipped) > 1e-9 * area(&polygon.pnts)
+}
+
+/// Whether `p` lies within the polygon, which may be concave (even-odd rule).
+pub fn polygon_contains(polygon: &Polygon, p: &Point) -> bool {
+    let pnts = &polygon.pnts;
+    pnts.iter()
+        .zip(pnts.iter().cycle().skip(1))
+        .filter(|(a, b)| {
+            (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Center of mass of a polygon's area, falling back
+/// to the mean of its points for degenerate polygons.
+pub fn centroid(pnts: &[Point]) -> Point {
+    let (mut x, mut y, mut doubled_area) = (0.0, 0.0, 0.0);
+    for (a, b) in pnts.iter().zip(pnts.iter().cycle().skip(1)) {
+        let cross = a.x * b.y - b.x * a.y;
+        x += (a.x + b.x) * cross;
+        y += (a.y + b.y) * cross;
+        doubled_area += cross;
+    }
+
+    if doubled_area.abs() > f64::EPSILON {
+        Point {
+            x: x / (3.0 * doubled_area),
+            y: y / (3.0 * doubled_area),
+        }
+    } else {
+        let count = pnts.len().max(1) as f64;
+        Point {
+            x: pnts.iter().map(|p| p.x).sum::<f64>() / count,
+            y: pnts.iter().map(|p| p.y).sum::<f64>() / count,
+        }
+    }
+}
+
+/// Searches for the number of rows and columns that yields close to `n` tiles
+/// without exceeding `MAX_TILE_ASPECT_RATIO`. Among equally close candidates,
+/// the one with the squarest tiles wins.
+fn grid_dimensions(n: usize, w: f64, h: f64) -> (usize, usize) {
+    let n = n.max(1);
+    let max_skew = MAX_TILE_ASPECT_RATIO.ln();
+    itertools::iproduct!(1..=n, 1..=n)
+        .map(|(rows, cols)| {
+            let skew = ((w / cols as f64) / (h / rows as f64)).ln().abs();
+            (rows, cols, skew)
+        })
+        .min_by(|(rows_a, cols_a, skew_a), (rows_b, cols_b, skew_b)| {
+            (*skew_a > max_skew, (rows_a * cols_a).abs_diff(n))
+                .cmp(&(*skew_b > max_skew, (rows_b * cols_b).abs_diff(n)))
+                .then(skew_a.total_cmp(skew_b))
+        })
+        .map(|(rows, cols, _)| (rows, cols))
+        .unwrap_or((1, 1))
+}
+
+/// Splits `length` into `count` parts, shifting each inner cut
+/// by up to a quarter of the regular part size.
+fn jittered_cuts(count: usize, length: f64, rng: &mut impl Rng) -> Vec<f64> {
+    let step = length / count as f64;
+    (0..=count)
+        .map(|i| {
+            let cut = i as f64 * step;
+            if i == 0 || i == count {
+                cut
+            } else {
+                cut + rng.gen_range(-0.25..0.25) * step
+            }
+        })
+        .collect()
+}
+
+fn rectangle(x: f64, y: f64, w: f64, h: f64) -> Polygon {
+    Polygon {
+        pnts: vec![
+            Point { x, y },
+            Point { x: x + w, y },
+            Point { x: x + w, y: y + h },
+            Point { x, y: y + h },
+        ],
+    }
+}
+
 impl From<delaunator::Point> for Point {
     fn from(p: delaunator::Point) -> Self {
         Self { x: p.x, y: p.y }
     }
 }
 
-pub fn cover_triangles(n: usize, w: f64, h: f64) -> Vec<Polygon> {
-    let mut points = random_points_cell(n, w, h);
+pub fn cover_triangles(
+    n: usize,
+    w: f64,
+    h: f64,
+    sampling: &PointSampling,
+    rng: &mut impl Rng,
+) -> Vec<Polygon> {
+    let mut points = sample_points(n, w, h, sampling, rng);
     points.append(&mut vec![
         delaunator::Point { x: 0.0, y: 0.0 },
         delaunator::Point { x: w, y: 0.0 },
@@ -65,12 +585,274 @@ pub fn cover_triangles(n: usize, w: f64, h: f64) -> Vec<Polygon> {
     triangles
 }
 
+/// Each cell contains the part of the plane that is closer to its seed point
+/// than to any other seed point. The seeds are distributed the same way as
+/// the points of the triangle covering.
+pub fn cover_voronoi(
+    n: usize,
+    w: f64,
+    h: f64,
+    sampling: &PointSampling,
+    rng: &mut impl Rng,
+) -> Vec<Polygon> {
+    let seeds = sample_points(n, w, h, sampling, rng);
+    let bounds = rectangle(0.0, 0.0, w, h);
+    let neighbors = delaunay_neighbors(&seeds);
+
+    let cells: Vec<_> = seeds
+        .iter()
+        .zip(&neighbors)
+        .map(|(seed, neighbors)| {
+            // Cut away everything that is closer to any of the neighboring seeds.
+            let pnts = neighbors.iter().fold(bounds.pnts.clone(), |cell, &other| {
+                let other = &seeds[other];
+                let normal = Point {
+                    x: other.x - seed.x,
+                    y: other.y - seed.y,
+                };
+                let offset = (normal.x * (seed.x + other.x) + normal.y * (seed.y + other.y)) / 2.0;
+                clip_half_plane(&cell, &normal, offset)
+            });
+            Polygon { pnts }
+        })
+        .filter(|cell| cell.pnts.len() >= 3)
+        .collect();
+
+    log::debug!("Created {} voronoi cells.", cells.len());
+
+    cells
+}
+
+/// Indices of the points each point shares an edge of the Delaunay triangulation with.
+/// Only these bound a point's Voronoi cell, hence clipping against all other points is not needed.
+fn delaunay_neighbors(points: &[delaunator::Point]) -> Vec<Vec<usize>> {
+    let triangulation = delaunator::triangulate(points);
+    if triangulation.triangles.is_empty() {
+        // Too few or collinear points, every point may bound every other's cell.
+        return (0..points.len())
+            .map(|i| (0..points.len()).filter(|&j| j != i).collect())
+            .collect();
+    }
+
+    let mut neighbors = vec![Vec::new(); points.len()];
+    for t in triangulation.triangles.chunks(3) {
+        for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+    }
+    for point_neighbors in &mut neighbors {
+        point_neighbors.sort_unstable();
+        point_neighbors.dedup();
+    }
+    neighbors
+}
+
+/// Covers the plane with flat-topped regular hexagons. Hexagons crossing the
+/// border are clipped. Their size is chosen such that the number of (partial)
+/// hexagons is as close as possible to `n`.
+pub fn cover_hexagons(n: usize, w: f64, h: f64) -> Vec<Polygon> {
+    let hexagon_area = 3.0 * 3f64.sqrt() / 2.0;
+    let initial_radius = (w * h / (n.max(1) as f64 * hexagon_area)).sqrt();
+
+    // Partial hexagons along the border make the count hard to predict,
+    // hence try a couple of sizes around the initial estimate.
+    let hexagons = (50..=200)
+        .map(|percent| hexagon_grid(initial_radius * percent as f64 / 100.0, w, h))
+        .min_by_key(|hexagons| hexagons.len().abs_diff(n))
+        .unwrap_or_default();
+
+    log::debug!("Created {} hexagons.", hexagons.len());
+
+    hexagons
+}
+
+fn hexagon_grid(radius: f64, w: f64, h: f64) -> Vec<Polygon> {
+    let col_step = 1.5 * radius;
+    let row_step = 3f64.sqrt() * radius;
+    let cols = (w / col_step).ceil() as usize;
+    let rows = (h / row_step).ceil() as usize;
+
+    itertools::iproduct!(0..=cols, 0..=rows)
+        .filter_map(|(col, row)| {
+            let center_x = col as f64 * col_step;
+            // Every other column is shifted by half a row.
+            let center_y = (row as f64 + (col % 2) as f64 / 2.0) * row_step;
+            let pnts = (0..6)
+                .map(|corner| {
+                    let angle = std::f64::consts::FRAC_PI_3 * corner as f64;
+                    Point {
+                        x: center_x + radius * angle.cos(),
+                        y: center_y + radius * angle.sin(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            clip_to_bounds(&pnts, w, h)
+        })
+        .collect()
+}
+
+/// Clips a polygon to the `w` × `h` plane.
+/// Returns `None` if nothing (or only a degenerate part) remains.
+fn clip_to_bounds(pnts: &[Point], w: f64, h: f64) -> Option<Polygon> {
+    let borders = [
+        (Point { x: -1.0, y: 0.0 }, 0.0),
+        (Point { x: 1.0, y: 0.0 }, w),
+        (Point { x: 0.0, y: -1.0 }, 0.0),
+        (Point { x: 0.0, y: 1.0 }, h),
+    ];
+    let pnts = borders
+        .iter()
+        .fold(pnts.to_vec(), |clipped, (normal, offset)| {
+            clip_half_plane(&clipped, normal, *offset)
+        });
+
+    // Polygons merely touching a border collapse to a line or point.
+    if pnts.len() >= 3 && area(&pnts) > 1e-9 * w * h {
+        Some(Polygon { pnts })
+    } else {
+        None
+    }
+}
+
+fn area(pnts: &[Point]) -> f64 {
+    pnts.iter()
+        .zip(pnts.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<f64>()
+        .abs()
+        / 2.0
+}
+
+/// Clips a polygon to the half-plane of all points `p` with `normal · p <= offset`.
+/// Should a concave polygon fall apart, its parts remain connected along the cut.
+fn clip_half_plane(pnts: &[Point], normal: &Point, offset: f64) -> Vec<Point> {
+    let side = |p: &Point| normal.x * p.x + normal.y * p.y - offset;
+
+    let mut clipped = Vec::with_capacity(pnts.len() + 1);
+    for (current, next) in pnts.iter().zip(pnts.iter().cycle().skip(1)) {
+        let (side_current, side_next) = (side(current), side(next));
+        if side_current <= 0.0 {
+            clipped.push(current.clone());
+        }
+        if side_current * side_next < 0.0 {
+            let t = side_current / (side_current - side_next);
+            clipped.push(Point {
+                x: current.x + t * (next.x - current.x),
+                y: current.y + t * (next.y - current.y),
+            });
+        }
+    }
+    clipped
+}
+
+fn sample_points(
+    n: usize,
+    w: f64,
+    h: f64,
+    sampling: &PointSampling,
+    rng: &mut impl Rng,
+) -> Vec<delaunator::Point> {
+    match sampling {
+        PointSampling::Grid => random_points_cell(n, w, h, rng),
+        PointSampling::PoissonDisk { min_distance } => {
+            let min_distance = min_distance
+                .map(|relative| relative * w.min(h))
+                .unwrap_or_else(|| (POISSON_DISK_DENSITY * w * h / n.max(1) as f64).sqrt());
+            let points = poisson_disk_points(min_distance, w, h, rng);
+            log::debug!(
+                "Sampled {} points with a minimum distance of {min_distance}.",
+                points.len()
+            );
+            points
+        }
+    }
+}
+
+/// Poisson-disk sampling places about `POISSON_DISK_DENSITY * w * h / d²` points
+/// for a minimum distance `d`. Used to derive `d` from the requested number of points.
+const POISSON_DISK_DENSITY: f64 = 0.7;
+
+/// Number of candidates tried around a point before it is considered saturated.
+const POISSON_DISK_CANDIDATES: usize = 30;
+
+/// Bridson's algorithm: points keep at least `min_distance` from each other
+/// and fill the plane evenly, without the pattern of a grid showing through.
+fn poisson_disk_points(
+    min_distance: f64,
+    w: f64,
+    h: f64,
+    rng: &mut impl Rng,
+) -> Vec<delaunator::Point> {
+    // Cells are small enough to hold at most a single point.
+    let cell_size = min_distance / std::f64::consts::SQRT_2;
+    let grid_cols = ((w / cell_size).ceil() as usize).max(1);
+    let grid_rows = ((h / cell_size).ceil() as usize).max(1);
+    let cell_of = |p: &delaunator::Point| {
+        let col = ((p.x / cell_size) as usize).min(grid_cols - 1);
+        let row = ((p.y / cell_size) as usize).min(grid_rows - 1);
+        (row, col)
+    };
+
+    let mut grid: Vec<Option<usize>> = vec![None; grid_rows * grid_cols];
+    let mut points = vec![delaunator::Point {
+        x: rng.gen_range(0.0..w),
+        y: rng.gen_range(0.0..h),
+    }];
+    let (row, col) = cell_of(&points[0]);
+    grid[row * grid_cols + col] = Some(0);
+    let mut active = vec![0];
+
+    while !active.is_empty() {
+        let active_idx = rng.gen_range(0..active.len());
+        let center = points[active[active_idx]].clone();
+
+        let candidate = (0..POISSON_DISK_CANDIDATES)
+            .map(|_| {
+                let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+                let distance = rng.gen_range(min_distance..2.0 * min_distance);
+                delaunator::Point {
+                    x: center.x + distance * angle.cos(),
+                    y: center.y + distance * angle.sin(),
+                }
+            })
+            .find(|candidate| {
+                if !(0.0..w).contains(&candidate.x) || !(0.0..h).contains(&candidate.y) {
+                    return false;
+                }
+                // Only the surrounding cells can contain points that are too close.
+                let (row, col) = cell_of(candidate);
+                itertools::iproduct!(
+                    row.saturating_sub(2)..(row + 3).min(grid_rows),
+                    col.saturating_sub(2)..(col + 3).min(grid_cols)
+                )
+                .filter_map(|(r, c)| grid[r * grid_cols + c])
+                .all(|other| {
+                    let (dx, dy) = (points[other].x - candidate.x, points[other].y - candidate.y);
+                    dx * dx + dy * dy >= min_distance * min_distance
+                })
+            });
+
+        match candidate {
+            Some(candidate) => {
+                let (row, col) = cell_of(&candidate);
+                grid[row * grid_cols + col] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+            }
+            None => {
+                active.swap_remove(active_idx);
+            }
+        }
+    }
+
+    points
+}
+
 /// To improve the shape of the triangles, randomly distribute
 /// points into cells instead of only on the plane itself.
 /// Otherwise, we could end up with lengthy and pointy triangles.
-fn random_points_cell(n: usize, w: f64, h: f64) -> Vec<delaunator::Point> {
-    let mut rng = rand::thread_rng();
-
+fn random_points_cell(n: usize, w: f64, h: f64, rng: &mut impl Rng) -> Vec<delaunator::Point> {
     let grid_cols = (n as f64).sqrt().ceil() as usize;
     let grid_rows = (n as f64 / grid_cols as f64).ceil() as usize;
     let cell_width = w / (grid_cols as f64);
@@ -100,8 +882,61 @@ fn random_points_cell(n: usize, w: f64, h: f64) -> Vec<delaunator::Point> {
         points.push(delaunator::Point {
             x: rng.gen_range(0.0..w),
             y: rng.gen_range(0.0..h),
-            });
+        });
     }
 
     points
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn rectangles_match_count() {
+        for (w, h) in [(1600.0, 900.0), (1000.0, 1000.0), (900.0, 1600.0)] {
+            for n in [1, 12, 20, 24, 50, 100] {
+                assert_eq!(cover_rectangles(n, w, h).len(), n, "{n} on {w}x{h}");
+            }
+        }
+    }
+
+    #[test]
+    fn rectangles_prefer_square_tiles_over_exact_count() {
+        // 2x5 tiles would be too narrow on a square plane.
+        assert_eq!(grid_dimensions(10, 1000.0, 1000.0), (3, 3));
+        assert_eq!(grid_dimensions(25, 3000.0, 1000.0), (3, 8));
+    }
+
+    #[test]
+    fn rings_match_count() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for (w, h) in [(1600.0, 900.0), (1000.0, 1000.0), (900.0, 1600.0)] {
+            let center = Point {
+                x: w / 2.0,
+                y: h / 2.0,
+            };
+            for n in [4, 10, 25, 50, 100] {
+                let count = cover_rings(n, w, h, &center, &mut rng).len();
+                assert_eq!(count, n, "{n} on {w}x{h}");
+            }
+        }
+    }
+
+    #[test]
+    fn rings_around_off_center_focus_come_close_to_count() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for (w, h) in [(1600.0, 900.0), (1000.0, 1000.0), (900.0, 1600.0)] {
+            let focus = Point {
+                x: w / 5.0,
+                y: h / 3.0,
+            };
+            for n in [4, 10, 25, 50, 100] {
+                let count = cover_rings(n, w, h, &focus, &mut rng).len();
+                assert!(count.abs_diff(n) <= 1, "{count} instead of {n} on {w}x{h}");
+            }
+        }
+    }
+}
@@ -1,24 +1,265 @@
-use chrono::{Datelike, NaiveDateTime};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use rand::seq::{IteratorRandom, SliceRandom};
 use rand::Rng;
 
+use crate::common::{CustomQuestion, GpsLocation, ImageWithMeta, Label, QuestionType};
+use crate::gazetteer::places_by_distance;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+/// Meteorological seasons of the northern hemisphere.
+const SEASONS: [&str; 4] = ["Spring", "Summer", "Autumn", "Winter"];
+const TIMES_OF_DAY: [&str; 4] = ["Morning", "Afternoon", "Evening", "Night"];
+/// Places closer than this (in kilometers) to where a picture was taken
+/// are not used as wrong answers, as they could be right as well.
+const MIN_DISTRACTOR_DISTANCE: f64 = 100.0;
+/// Pictures taken farther than this (in kilometers) from any known place
+/// can't be named by one, hence no location question is asked.
+const MAX_PLACE_DISTANCE: f64 = 50.0;
+/// Wrong answers are picked from this many places next to the right one.
+const DISTRACTOR_CANDIDATES: usize = 6;
+
 #[derive(Default)]
 pub struct QuestionAndAnswers {
     pub question: String,
     pub answers: Vec<String>,
     pub idx_correct: usize,
+    pub hints: Vec<String>,
+    /// Shown once the question has been answered.
+    pub explanation: Option<String>,
+    /// The kind of question, if picked by `pick_question`.
+    pub question_type: Option<QuestionType>,
 }
 
-pub fn simple_year_question(date: &NaiveDateTime) -> QuestionAndAnswers {
+pub fn simple_year_question(date: &NaiveDateTime, rng: &mut impl Rng) -> QuestionAndAnswers {
     let year_taken = date.date().year();
     let year_now = chrono::Utc::now().year();
     let number_of_answers = 3;
     let max_offset = std::cmp::min(number_of_answers - 1, year_now - year_taken);
-    let offset = rand::thread_rng().gen_range(0..=max_offset);
+    let offset = rng.gen_range(0..=max_offset);
     QuestionAndAnswers {
         question: "Which year was the picture taken?".into(),
         answers: (year_taken - (number_of_answers - 1) + offset..=year_taken + offset)
             .map(|i| i.to_string())
             .collect(),
         idx_correct: ((number_of_answers - 1) - offset) as usize,
+        ..Default::default()
+    }
+}
+
+/// Picks one of the given question types at random, among those that can be
+/// answered from the image's metadata, and builds the question.
+pub fn pick_question(
+    question_types: &[QuestionType],
+    image: &ImageWithMeta,
+    rng: &mut impl Rng,
+) -> Option<QuestionAndAnswers> {
+    let answerable = question_types
+        .iter()
+        .filter(|question_type| match question_type {
+            QuestionType::Location => image.location.as_ref().is_some_and(is_near_known_place),
+            QuestionType::Folder => has_siblings(&image.folder_label),
+            QuestionType::FileName => has_siblings(&image.file_label),
+            QuestionType::Custom => !image.questions.is_empty(),
+            _ => image.date_taken.is_some(),
+        })
+        .collect::<Vec<_>>();
+
+    let question_type = *answerable.choose(rng)?;
+    let qna = match (question_type, &image.date_taken, &image.location) {
+        (QuestionType::Location, _, Some(location)) => location_question(location, rng),
+        (QuestionType::Folder, _, _) => label_question(image.folder_label.as_ref()?, rng),
+        (QuestionType::FileName, _, _) => label_question(image.file_label.as_ref()?, rng),
+        (QuestionType::Custom, _, _) => custom_question(image.questions.choose(rng)?),
+        (QuestionType::Year, Some(date), _) => simple_year_question(date, rng),
+        (QuestionType::Month, Some(date), _) => month_question(date, rng),
+        (QuestionType::Season, Some(date), location) => season_question(date, location.as_ref()),
+        (QuestionType::Weekday, Some(date), _) => weekday_question(date, rng),
+        (QuestionType::TimeOfDay, Some(date), _) => time_of_day_question(date),
+        (QuestionType::YearsAgo, Some(date), _) => years_ago_question(date, rng),
+        _ => return None,
+    };
+    Some(QuestionAndAnswers {
+        question_type: Some(question_type.clone()),
+        ..qna
+    })
+}
+
+pub fn month_question(date: &NaiveDateTime, rng: &mut impl Rng) -> QuestionAndAnswers {
+    let (answers, idx_correct) = pick_answers(&MONTHS, date.month0() as usize, 3, rng);
+    QuestionAndAnswers {
+        question: "Which month was the picture taken?".into(),
+        answers,
+        idx_correct,
+        ..Default::default()
     }
 }
+
+/// Seasons are those of the northern hemisphere, unless the location tells otherwise.
+pub fn season_question(date: &NaiveDateTime, location: Option<&GpsLocation>) -> QuestionAndAnswers {
+    // March to May is spring, ..., December to February is winter.
+    let northern = (date.month0() + 10) % 12 / 3;
+    let southern = location.is_some_and(|location| location.latitude < 0.0);
+    QuestionAndAnswers {
+        question: "Which season was the picture taken?".into(),
+        answers: SEASONS.iter().map(|s| s.to_string()).collect(),
+        idx_correct: if southern {
+            (northern + 2) % 4
+        } else {
+            northern
+        } as usize,
+        ..Default::default()
+    }
+}
+
+pub fn weekday_question(date: &NaiveDateTime, rng: &mut impl Rng) -> QuestionAndAnswers {
+    let weekday = date.weekday().num_days_from_monday() as usize;
+    let (answers, idx_correct) = pick_answers(&WEEKDAYS, weekday, 3, rng);
+    QuestionAndAnswers {
+        question: "Which day of the week was the picture taken?".into(),
+        answers,
+        idx_correct,
+        ..Default::default()
+    }
+}
+
+pub fn time_of_day_question(date: &NaiveDateTime) -> QuestionAndAnswers {
+    let idx_correct = match date.hour() {
+        5..=11 => 0,
+        12..=16 => 1,
+        17..=21 => 2,
+        _ => 3,
+    };
+    QuestionAndAnswers {
+        question: "What time of day was the picture taken?".into(),
+        answers: TIMES_OF_DAY.iter().map(|s| s.to_string()).collect(),
+        idx_correct,
+        ..Default::default()
+    }
+}
+
+pub fn years_ago_question(date: &NaiveDateTime, rng: &mut impl Rng) -> QuestionAndAnswers {
+    let years_ago = (chrono::Utc::now().year() - date.date().year()).max(0);
+    let number_of_answers = 3;
+    // Unlike years, the answers must not be negative.
+    let offset = rng.gen_range(0..=std::cmp::min(number_of_answers - 1, years_ago));
+    QuestionAndAnswers {
+        question: "How many years ago was the picture taken?".into(),
+        answers: (years_ago - offset..years_ago - offset + number_of_answers)
+            .map(|i| i.to_string())
+            .collect(),
+        idx_correct: offset as usize,
+        ..Default::default()
+    }
+}
+
+/// Names the closest known place, with other places nearby as wrong answers.
+/// Only sensible if the location is near a known place, see `pick_question`.
+pub fn location_question(location: &GpsLocation, rng: &mut impl Rng) -> QuestionAndAnswers {
+    let mut places = places_by_distance(location).into_iter();
+    let (_, correct) = places.next().unwrap_or_default();
+
+    let wrong = places
+        .filter(|(distance, _)| *distance >= MIN_DISTRACTOR_DISTANCE)
+        .take(DISTRACTOR_CANDIDATES)
+        .map(|(_, name)| name)
+        .choose_multiple(rng, 2);
+    let (answers, idx_correct) = shuffle_answers(correct, wrong, rng);
+
+    QuestionAndAnswers {
+        question: "Where was this taken?".into(),
+        answers,
+        idx_correct,
+        ..Default::default()
+    }
+}
+
+/// Asks for the label, e.g. a folder name, with other labels of the same kind as wrong answers.
+pub fn label_question(label: &Label, rng: &mut impl Rng) -> QuestionAndAnswers {
+    let wrong = label.siblings.iter().cloned().choose_multiple(rng, 2);
+    let (answers, idx_correct) = shuffle_answers(label.name.clone(), wrong, rng);
+
+    QuestionAndAnswers {
+        question: "What does the picture show?".into(),
+        answers,
+        idx_correct,
+        ..Default::default()
+    }
+}
+
+/// Question written by hand, e.g. in a sidecar file.
+pub fn custom_question(custom: &CustomQuestion) -> QuestionAndAnswers {
+    QuestionAndAnswers {
+        question: custom.question.clone(),
+        answers: custom.answers.clone(),
+        idx_correct: custom.correct,
+        hints: custom.hints.clone(),
+        explanation: custom.explanation.clone(),
+        ..Default::default()
+    }
+}
+
+fn has_siblings(label: &Option<Label>) -> bool {
+    label.as_ref().is_some_and(|l| !l.siblings.is_empty())
+}
+
+fn is_near_known_place(location: &GpsLocation) -> bool {
+    places_by_distance(location)
+        .first()
+        .is_some_and(|(distance, _)| *distance <= MAX_PLACE_DISTANCE)
+}
+
+/// The correct answer among the wrong ones, at a random position.
+fn shuffle_answers(
+    correct: String,
+    wrong: Vec<String>,
+    rng: &mut impl Rng,
+) -> (Vec<String>, usize) {
+    let mut answers = wrong;
+    answers.push(correct.clone());
+    answers.shuffle(rng);
+
+    let idx_correct = answers.iter().position(|a| *a == correct).unwrap();
+    (answers, idx_correct)
+}
+
+/// The correct option plus randomly picked other ones, in the options' order.
+fn pick_answers(
+    options: &[&str],
+    correct: usize,
+    count: usize,
+    rng: &mut impl Rng,
+) -> (Vec<String>, usize) {
+    let mut picked = (0..options.len())
+        .filter(|i| *i != correct)
+        .choose_multiple(rng, count - 1);
+    picked.push(correct);
+    picked.sort();
+
+    let idx_correct = picked.iter().position(|i| *i == correct).unwrap();
+    (
+        picked.into_iter().map(|i| options[i].to_string()).collect(),
+        idx_correct,
+    )
+}
@@ -0,0 +1,114 @@
+use image::imageops::FilterType;
+
+use crate::common::{Point, Polygon};
+use crate::plane_covering::{centroid, polygon_contains};
+
+/// Images are analyzed at this (maximum) resolution, which is plenty
+/// to tell busy parts from plain background.
+const SALIENCY_RESOLUTION: u32 = 64;
+
+/// Rough estimate of how much information the different parts of an image hold,
+/// based on the density of edges.
+pub struct SaliencyMap {
+    width: usize,
+    height: usize,
+    /// Row-major, normalized to `0.0..=1.0`.
+    values: Vec<f64>,
+}
+
+impl SaliencyMap {
+    pub fn from_image(bytes: &[u8]) -> Result<Self, String> {
+        let luma = image::load_from_memory(bytes)
+            .map_err(|e| e.to_string())?
+            .resize(
+                SALIENCY_RESOLUTION,
+                SALIENCY_RESOLUTION,
+                FilterType::Triangle,
+            )
+            .to_luma8();
+        let (width, height) = (luma.width() as usize, luma.height() as usize);
+        let pixel = |x: usize, y: usize| {
+            luma.get_pixel(x.min(width - 1) as u32, y.min(height - 1) as u32)[0] as f64
+        };
+
+        // Sobel gradient magnitude ...
+        let gradients = itertools::iproduct!(0..height, 0..width)
+            .map(|(y, x)| {
+                let (left, right) = (x.saturating_sub(1), x + 1);
+                let (up, down) = (y.saturating_sub(1), y + 1);
+                let gx = pixel(right, up) + 2.0 * pixel(right, y) + pixel(right, down)
+                    - pixel(left, up)
+                    - 2.0 * pixel(left, y)
+                    - pixel(left, down);
+                let gy = pixel(left, down) + 2.0 * pixel(x, down) + pixel(right, down)
+                    - pixel(left, up)
+                    - 2.0 * pixel(x, up)
+                    - pixel(right, up);
+                (gx * gx + gy * gy).sqrt()
+            })
+            .collect::<Vec<_>>();
+
+        // ... averaged over the neighborhood to turn single edges into edge density.
+        let mut values = itertools::iproduct!(0..height, 0..width)
+            .map(|(y, x)| {
+                let neighbors = itertools::iproduct!(
+                    y.saturating_sub(1)..(y + 2).min(height),
+                    x.saturating_sub(1)..(x + 2).min(width)
+                )
+                .map(|(ny, nx)| gradients[ny * width + nx])
+                .collect::<Vec<_>>();
+                neighbors.iter().sum::<f64>() / neighbors.len() as f64
+            })
+            .collect::<Vec<_>>();
+        let max = values.iter().cloned().fold(f64::EPSILON, f64::max);
+        values.iter_mut().for_each(|v| *v /= max);
+
+        Ok(SaliencyMap {
+            width,
+            height,
+            values,
+        })
+    }
+
+    /// Mean saliency within a polygon, given in the coordinates of a `w` × `h` image.
+    pub fn polygon_saliency(&self, polygon: &Polygon, w: f64, h: f64) -> f64 {
+        let (cell_w, cell_h) = (w / self.width as f64, h / self.height as f64);
+        let inside = itertools::iproduct!(0..self.height, 0..self.width)
+            .filter(|(y, x)| {
+                let center = Point {
+                    x: (*x as f64 + 0.5) * cell_w,
+                    y: (*y as f64 + 0.5) * cell_h,
+                };
+                polygon_contains(polygon, &center)
+            })
+            .map(|(y, x)| self.values[y * self.width + x])
+            .collect::<Vec<_>>();
+
+        if inside.is_empty() {
+            // The polygon is smaller than a single cell.
+            let center = centroid(&polygon.pnts);
+            let x = ((center.x / cell_w) as usize).min(self.width - 1);
+            let y = ((center.y / cell_h) as usize).min(self.height - 1);
+            self.values[y * self.width + x]
+        } else {
+            inside.iter().sum::<f64>() / inside.len() as f64
+        }
+    }
+}
+
+/// Sorts a covering such that the polygons with the least information,
+/// e.g. plain background, are uncovered first and the subject is uncovered last.
+pub fn order_by_saliency(
+    covering: Vec<Polygon>,
+    map: &SaliencyMap,
+    w: f64,
+    h: f64,
+) -> Vec<Polygon> {
+    let mut ranked = covering
+        .into_iter()
+        .map(|polygon| (map.polygon_saliency(&polygon, w, h), polygon))
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(rank_a, _), (rank_b, _)| rank_a.total_cmp(rank_b));
+
+    ranked.into_iter().map(|(_, polygon)| polygon).collect()
+}
@@ -0,0 +1,81 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::common::Scramble;
+use crate::plane_covering::cover_rectangles;
+
+/// Cuts the plane into the tiles of `cover_rectangles` and shuffles their positions.
+/// Replaying the swaps one by one puts the tiles back in place.
+pub fn scramble_tiles(n: usize, w: f64, h: f64, rng: &mut impl Rng) -> Scramble {
+    let tiles = cover_rectangles(n, w, h);
+    let slots = shuffled_slots(tiles.len(), rng);
+    let swaps = unscramble_swaps(&slots, rng);
+
+    Scramble {
+        tiles,
+        slots,
+        swaps,
+    }
+}
+
+/// Random permutation, where `slots[i]` is the tile shown at the position of tile `i`.
+/// Avoids the identity, which wouldn't be much of a puzzle.
+fn shuffled_slots(count: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut slots = (0..count).collect::<Vec<_>>();
+    if count < 2 {
+        return slots;
+    }
+    while slots.iter().enumerate().all(|(slot, tile)| slot == *tile) {
+        slots.shuffle(rng);
+    }
+    slots
+}
+
+/// Swaps of two slots each, such that each swap moves (at least) one tile to its
+/// correct place. The tiles are fixed in random order, not slot by slot.
+fn unscramble_swaps(slots: &[usize], rng: &mut impl Rng) -> Vec<(usize, usize)> {
+    let mut slots = slots.to_vec();
+    let mut misplaced = (0..slots.len())
+        .filter(|slot| slots[*slot] != *slot)
+        .collect::<Vec<_>>();
+    misplaced.shuffle(rng);
+
+    let mut swaps = Vec::new();
+    for tile in misplaced {
+        // May have been fixed by an earlier swap as a side effect.
+        if slots[tile] == tile {
+            continue;
+        }
+        let current = slots.iter().position(|t| *t == tile).unwrap();
+        slots.swap(tile, current);
+        swaps.push((tile, current));
+    }
+    swaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn replaying_swaps_restores_tiles() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for n in 10..=100 {
+            let scramble = scramble_tiles(n, 16.0, 9.0, &mut rng);
+            let mut slots = scramble.slots.clone();
+            for (a, b) in &scramble.swaps {
+                slots.swap(*a, *b);
+            }
+            assert_eq!(slots, (0..slots.len()).collect::<Vec<_>>(), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_scramble() {
+        let scramble = |seed| scramble_tiles(30, 16.0, 9.0, &mut ChaCha8Rng::seed_from_u64(seed));
+        assert_eq!(scramble(42), scramble(42));
+        assert_ne!(scramble(42).slots, scramble(43).slots);
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tauri_plugin_fs::FilePath;
+
+use crate::common::{CustomQuestion, KeyRegion};
+
+/// Optional annotations of an image, stored next to it as `<image file name>.json`,
+/// e.g. `photo.jpg.json`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Sidecar {
+    pub key_regions: Vec<KeyRegion>,
+    pub questions: Vec<CustomQuestion>,
+}
+
+pub fn sidecar_path(image_path: &Path) -> PathBuf {
+    let mut file_name = image_path.as_os_str().to_owned();
+    file_name.push(".json");
+    PathBuf::from(file_name)
+}
+
+/// Reads the sidecar of an image. Images without a sidecar yield an empty one.
+/// Only regular paths are supported, urls such as android's content urls
+/// don't allow to find sibling files.
+pub fn read_sidecar(image_path: &FilePath) -> Result<Sidecar, String> {
+    let FilePath::Path(image_path) = image_path else {
+        return Ok(Sidecar::default());
+    };
+
+    let path = sidecar_path(image_path);
+    if !path.is_file() {
+        return Ok(Sidecar::default());
+    }
+
+    log::debug!("Reading sidecar {:?}", path);
+    std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+        .and_then(validate)
+        .map_err(|e| format!("Invalid sidecar '{}': {}", path.display(), e))
+}
+
+fn validate(sidecar: Sidecar) -> Result<Sidecar, String> {
+    match sidecar
+        .questions
+        .iter()
+        .find(|q| q.correct >= q.answers.len())
+    {
+        Some(q) => Err(format!(
+            "The correct answer of '{}' must be one of its {} answers.",
+            q.question,
+            q.answers.len()
+        )),
+        None => Ok(sidecar),
+    }
+}
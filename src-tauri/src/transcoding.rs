@@ -0,0 +1,113 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use std::io::Cursor;
+
+/// Images the webview can't display reliably on every platform,
+/// which are decoded and re-encoded before they are sent.
+/// AVIF and HEIC depend on native libraries, hence are optional features.
+pub const TRANSCODED_IMAGE_EXTENSIONS: &[&str] = &[
+    "tif",
+    "tiff",
+    "bmp",
+    #[cfg(feature = "avif")]
+    "avif",
+    #[cfg(feature = "heic")]
+    "heif",
+    #[cfg(feature = "heic")]
+    "heic",
+];
+
+const JPEG_QUALITY: u8 = 90;
+
+/// Whether an image is wider or higher than `max_resolution`, reading only its header.
+/// GIFs are never considered too large, since downscaling would drop their animation.
+pub fn exceeds_resolution(bytes: &[u8], image_type: &str, max_resolution: Option<u32>) -> bool {
+    let Some(max_resolution) = max_resolution else {
+        return false;
+    };
+    image_type != "gif"
+        && ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok())
+            .is_some_and(|(width, height)| width.max(height) > max_resolution)
+}
+
+/// Decodes an image, turns it upright, downscales it to fit within `max_resolution`,
+/// and re-encodes it as JPEG, or as PNG if it is transparent.
+/// Returns the new bytes and their MIME subtype.
+pub fn transcode(
+    bytes: &[u8],
+    image_type: &str,
+    orientation: Orientation,
+    max_resolution: Option<u32>,
+) -> Result<(Vec<u8>, String), String> {
+    let image = match image_type {
+        // libheif applies the orientation itself.
+        #[cfg(feature = "heic")]
+        "heif" => decode_heif(bytes)?,
+        _ => {
+            let mut image = image::load_from_memory(bytes).map_err(|e| e.to_string())?;
+            image.apply_orientation(orientation);
+            image
+        }
+    };
+    log::debug!(
+        "Transcoding {} image of {}x{} ({:?}, max. {:?}).",
+        image_type,
+        image.width(),
+        image.height(),
+        orientation,
+        max_resolution
+    );
+    let image = match max_resolution {
+        Some(max) if image.width().max(image.height()) > max => {
+            image.resize(max, max, FilterType::Triangle)
+        }
+        _ => image,
+    };
+
+    let mut transcoded = Vec::new();
+    if image.color().has_alpha() {
+        image
+            .write_to(&mut Cursor::new(&mut transcoded), ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok((transcoded, "png".into()))
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut transcoded, JPEG_QUALITY))
+            .map_err(|e| e.to_string())?;
+        Ok((transcoded, "jpeg".into()))
+    }
+}
+
+/// HEIF, and thus HEIC, is not supported by the `image` crate, libheif decodes it instead.
+#[cfg(feature = "heic")]
+fn decode_heif(bytes: &[u8]) -> Result<DynamicImage, String> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let context = HeifContext::read_from_bytes(bytes).map_err(|e| e.to_string())?;
+    let handle = context.primary_image_handle().map_err(|e| e.to_string())?;
+    let decoded = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)
+        .map_err(|e| e.to_string())?;
+    let plane = decoded
+        .planes()
+        .interleaved
+        .ok_or("HEIF image without interleaved plane.".to_string())?;
+
+    // Rows may be padded, hence copy them one by one.
+    let row_size = plane.width as usize * 3;
+    let pixels = plane
+        .data
+        .chunks(plane.stride)
+        .take(plane.height as usize)
+        .flat_map(|row| &row[..row_size])
+        .copied()
+        .collect();
+    image::RgbImage::from_raw(plane.width, plane.height, pixels)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or("Invalid HEIF image data.".to_string())
+}
@@ -0,0 +1,95 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::common::{RevealState, UncoveringStrategy};
+
+/// How often the ticker checks whether the next polygon is due.
+const TICK: Duration = Duration::from_millis(50);
+/// Limits how much faster, or slower, than its interval the timed strategy uncovers.
+const MAX_SPEED: f64 = 64.0;
+
+/// Progress of a backend-driven uncovering of the current covering.
+pub struct Uncovering {
+    /// Number of polygons of the current covering.
+    pub total: usize,
+    /// Number of polygons that have not been uncovered yet.
+    pub remaining: usize,
+    pub paused: bool,
+    /// Factor applied to the strategy's interval, e.g. 2.0 uncovers twice as fast.
+    pub speed: f64,
+    last_step: Instant,
+}
+
+impl Default for Uncovering {
+    fn default() -> Self {
+        Uncovering {
+            total: 0,
+            remaining: 0,
+            paused: false,
+            speed: 1.0,
+            last_step: Instant::now(),
+        }
+    }
+}
+
+impl Uncovering {
+    /// Starts over uncovering `total` polygons.
+    pub fn restart(&mut self, total: usize) {
+        self.total = total;
+        self.remaining = total;
+        self.paused = false;
+        self.last_step = Instant::now();
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Multiplies the speed by `factor`, within sane limits, and returns the new speed.
+    pub fn speed_up(&mut self, factor: f64) -> f64 {
+        self.speed = (self.speed * factor).clamp(1.0 / MAX_SPEED, MAX_SPEED);
+        self.speed
+    }
+
+    /// Continues with a full interval until the next polygon.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.last_step = Instant::now();
+    }
+
+    /// Checks whether the next polygon is due and, if so, accounts for it.
+    fn step(&mut self, interval: Duration, now: Instant) -> bool {
+        // Huge intervals don't fit a duration once slowed down, they are never due then.
+        let interval = Duration::try_from_secs_f64(interval.as_secs_f64() / self.speed)
+            .unwrap_or(Duration::MAX);
+        if self.paused || self.remaining == 0 || now.duration_since(self.last_step) < interval {
+            return false;
+        }
+        self.remaining -= 1;
+        self.last_step = now;
+        true
+    }
+}
+
+/// Periodically emits 'uncover-next' to the frontend whenever
+/// the timed strategy is active and the next polygon is due.
+pub fn spawn_ticker(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK);
+
+        let state = app.state::<Mutex<RevealState>>();
+        let mut state = state.lock().unwrap();
+        let UncoveringStrategy::Timed { interval_ms } = state.settings.uncovering_strategy else {
+            continue;
+        };
+        if state
+            .uncovering
+            .step(Duration::from_millis(interval_ms), Instant::now())
+        {
+            let remaining = state.uncovering.remaining;
+            drop(state);
+            app.emit("uncover-next", remaining).unwrap();
+        }
+    });
+}
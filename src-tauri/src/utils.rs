@@ -1,6 +1,9 @@
 use std::path::PathBuf;
+use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 
+use crate::common::RevealState;
+
 fn path_result_to_str(result: tauri::Result<PathBuf>) -> String {
     match result {
         Ok(pb) => pb.to_str().unwrap_or("Non-UTF8 path").to_owned(),
@@ -8,6 +11,16 @@ fn path_result_to_str(result: tauri::Result<PathBuf>) -> String {
     }
 }
 
+/// Larger side of the largest monitor in physical pixels, if any monitor is known.
+pub fn largest_monitor_size(app_handle: &AppHandle) -> Option<u32> {
+    app_handle
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(|m| m.size().width.max(m.size().height))
+        .max()
+}
+
 pub fn debug_info(app_handle: &AppHandle) -> String {
     let mut s = String::new();
 
@@ -54,6 +67,16 @@ pub fn debug_info(app_handle: &AppHandle) -> String {
         ));
     }
 
+    s.push_str("\n… Game …\n");
+    s.push_str(&format!(
+        "Seed: {}\n",
+        app_handle
+            .state::<Mutex<RevealState>>()
+            .lock()
+            .unwrap()
+            .seed
+    ));
+
     s.push_str("\n… OS …\n");
     s.push_str(&format!("Platform: {}\n", tauri_plugin_os::platform()));
     s.push_str(&format!("Arch: {}\n", tauri_plugin_os::arch()));
@@ -1,4 +1,11 @@
-import { executeIfSettingsChanged, initializeSettingsListeners, loadSettings, resetSettings } from "./settings.js";
+import {
+  executeIfFolderScanChanged,
+  executeIfSettingsChanged,
+  initializeSettingsListeners,
+  loadSettings,
+  resetSettings,
+  setUncoveringOrder,
+} from "./settings.js";
 import { isAndroid, isMobile, printDebug } from "./utils.js";
 
 const { invoke } = window.__TAURI__.core;
@@ -14,6 +21,8 @@ const Action = Object.freeze({
   next: "n",
   previous: "p",
   uncover: "u",
+  toggleUncovering: "t",
+  speedUpUncovering: "+",
   clear: "c",
   reset: "r",
   settings: "s",
@@ -29,35 +38,70 @@ const state = {
   svgPolygons: [],
   svgPolygonsHideIdx: 0,
 
+  // Frame-based reveal modes show frames instead of uncovering polygons.
+  frames: [],
+  frameIdx: 0,
+  originalSrc: "",
+
+  // The zoom-out reveal mode shows crops of the image, from the smallest to the largest one.
+  crops: [],
+  cropIdx: 0,
+
+  // The tile-scramble mode swaps tiles back into place, one swap per step.
+  scramble: null,
+  scrambleIdx: 0,
+  scrambleSlots: [],
+
+  // Hints of the current question that have not been shown yet.
+  hints: [],
+
+  // Title of a deck that is about to be played.
+  deckTitle: null,
+  // The user's settings a deck has replaced while it is played.
+  settingsBeforeDeck: null,
+  // Where the current images come from, one of 'folder', 'deck', 'files' or 'examples'.
+  imageSource: null,
+
   settings: 0,
+  uncoveringPaused: false,
 };
 
 async function getImage(u) {
   try {
-    const revealObject = await invoke("get_image", { u: u, quizYear: state.inputQuizYear.checked });
+    const revealObject = await invoke("get_image", {
+      u: u,
+      questionTypes: [...state.inputQuizTypes].filter((input) => input.checked).map((input) => input.value),
+      zoomSteps: state.inputObjectType.value === "Zoom" ? Number(state.inputObjectCount.value) : null,
+    });
     state.image.setAttribute("hidden", "hidden");
     state.image.src = `data:image/${revealObject.image_type};base64,${revealObject.image}`;
+    state.crops = revealObject.crops;
+    state.subfolderSpan.textContent = revealObject.subfolder ? `(${revealObject.subfolder})` : "";
+
+    state.questionSpan.textContent = revealObject.question ?? "";
+    state.hints = revealObject.hints;
+    state.hintSpan.textContent = "";
+    state.hintButton.hidden = state.hints.length === 0;
+    state.explanationSpan.textContent = "";
 
     if (revealObject.question !== undefined) {
-      state.qnaAnswersDiv.innerHTML = "";
-      state.qnaAnswersDiv.innerHTML = revealObject.answers
-        .map(
-          (a, idx) => `<button
-            data-idx=${idx}
-            class='answer'>${a}
-          </button>`,
-        )
-        .join("\n");
-
-      for (const button of document.querySelectorAll("button.answer")) {
-        button.addEventListener("pointerup", () => {
-          if (Number(button.dataset.idx) === Number(revealObject.correct_answer)) {
-            button.classList.add("correct");
-          } else {
-            button.classList.add("wrong");
-          }
-        });
-      }
+      // Answers may come from sidecar files or shared decks, hence never insert them as HTML.
+      state.qnaAnswersDiv.replaceChildren(
+        ...revealObject.answers.map((a, idx) => {
+          const button = document.createElement("button");
+          button.className = "answer";
+          button.textContent = a;
+          button.addEventListener("pointerup", () => {
+            if (idx === Number(revealObject.correct_answer)) {
+              button.classList.add("correct");
+            } else {
+              button.classList.add("wrong");
+            }
+            state.explanationSpan.textContent = revealObject.explanation ?? "";
+          });
+          return button;
+        }),
+      );
     }
 
     // TODO promise fail not handled ...
@@ -69,7 +113,55 @@ async function getImage(u) {
   }
 }
 
+const frameModes = ["Pixelation", "Blur"];
+
+// Shows the current frame, or the original image once all frames have been shown.
+function showFrame() {
+  state.image.src = state.frameIdx < state.frames.length ? state.frames[state.frameIdx] : state.originalSrc;
+}
+
+// Shows the current crop, or the full image once all crops have been shown.
+function showCrop() {
+  const w = state.image.naturalWidth || 1;
+  const h = state.image.naturalHeight || 1;
+  const crop = state.crops[state.cropIdx] ?? { x: 0, y: 0, width: 1, height: 1 };
+  state.svg.setAttribute("viewBox", `${crop.x * w} ${crop.y * h} ${crop.width * w} ${crop.height * h}`);
+}
+
+// Bounding box of a tile, as used for view boxes.
+function tileBox(tile) {
+  const xs = tile.pnts.map((p) => p.x);
+  const ys = tile.pnts.map((p) => p.y);
+  const x = Math.min(...xs);
+  const y = Math.min(...ys);
+  return { x: x, y: y, width: Math.max(...xs) - x, height: Math.max(...ys) - y };
+}
+
+// Shows each slot's tile after the swaps up to the current step.
+function showScramble() {
+  const slots = [...state.scramble.slots];
+  for (const [a, b] of state.scramble.swaps.slice(0, state.scrambleIdx)) {
+    [slots[a], slots[b]] = [slots[b], slots[a]];
+  }
+  state.scrambleSlots.forEach((slot, idx) => {
+    const box = tileBox(state.scramble.tiles[slots[idx]]);
+    slot.setAttribute("viewBox", `${box.x} ${box.y} ${box.width} ${box.height}`);
+  });
+}
+
 function uncoverNext() {
+  if (state.scramble !== null) {
+    state.scrambleIdx = Math.min(state.scrambleIdx + 1, state.scramble.swaps.length);
+    showScramble();
+  }
+  if (state.crops.length > 0) {
+    state.cropIdx = Math.min(state.cropIdx + 1, state.crops.length);
+    showCrop();
+  }
+  if (state.frames.length > 0) {
+    state.frameIdx = Math.min(state.frameIdx + 1, state.frames.length);
+    showFrame();
+  }
   if (state.svgPolygons.length > 0) {
     const index = state.svgPolygonsHideIdx++ % state.svgPolygons.length;
     state.svgPolygons[index].style.opacity = "0";
@@ -81,6 +173,20 @@ function coverFull() {
     p.style.opacity = "1";
   }
   state.svgPolygonsHideIdx = 0;
+  if (state.frames.length > 0) {
+    state.frameIdx = 0;
+    showFrame();
+  }
+  if (state.crops.length > 0) {
+    state.cropIdx = 0;
+    showCrop();
+  }
+  if (state.scramble !== null) {
+    state.scrambleIdx = 0;
+    showScramble();
+  }
+  state.uncoveringPaused = false;
+  invoke("restart_uncovering");
 }
 
 function uncoverFull() {
@@ -88,19 +194,129 @@ function uncoverFull() {
     p.style.opacity = "0";
   }
   state.svgPolygonsHideIdx = 0;
+  if (state.frames.length > 0) {
+    state.frameIdx = state.frames.length;
+    showFrame();
+  }
+  if (state.crops.length > 0) {
+    state.cropIdx = state.crops.length;
+    showCrop();
+  }
+  if (state.scramble !== null) {
+    state.scrambleIdx = state.scramble.swaps.length;
+    showScramble();
+  }
+  state.uncoveringPaused = true;
+  invoke("pause_uncovering");
+}
+
+// Only has an effect if the timed uncovering strategy is active.
+async function toggleUncovering() {
+  if (state.uncoveringPaused) {
+    await invoke("resume_uncovering");
+  } else {
+    await invoke("pause_uncovering");
+  }
+  state.uncoveringPaused = !state.uncoveringPaused;
+}
+
+async function loadFrames(w, h, n) {
+  debug(`Requesting ${n} frames.`);
+  let revealFrames;
+  try {
+    revealFrames = await invoke("load_frames", {
+      n: n,
+      objectType: state.inputObjectType.value,
+    });
+  } catch (e) {
+    // E.g. SVGs can't be rendered to frames, hence they are covered by rectangles instead.
+    error(`Failed rendering frames, falling back to rectangles: ${e}`);
+    state.frames = [];
+    return loadPolygons(w, h, n, "Rectangles");
+  }
+
+  state.frames = revealFrames.frames.map((frame) => `data:image/${revealFrames.image_type};base64,${frame}`);
+  state.originalSrc = state.image.src;
+  state.frameIdx = 0;
+  showFrame();
+
+  state.svg.replaceChildren();
+  state.svgPolygons = [];
+  state.uncoveringPaused = false;
+  state.image.removeAttribute("hidden");
+}
+
+// The image is shown within the svg, whose view box is then moved from crop to crop.
+function loadZoom() {
+  const image = document.createElementNS("http://www.w3.org/2000/svg", "image");
+  image.setAttribute("href", state.image.src);
+  image.setAttribute("width", state.image.naturalWidth || 1);
+  image.setAttribute("height", state.image.naturalHeight || 1);
+  state.svg.replaceChildren(image);
+  state.svgPolygons = [];
+
+  state.cropIdx = 0;
+  showCrop();
+  state.uncoveringPaused = false;
+}
+
+// Each slot is a nested svg showing the whole image through the view box of a tile.
+async function loadScramble(w, h, n) {
+  debug(`Requesting scramble for ${w}x${h} with ${n}.`);
+  state.scramble = await invoke("load_scramble", { n: n, width: w, height: h });
+
+  state.svg.setAttribute("viewBox", `0 0 ${w} ${h}`);
+  state.svg.replaceChildren();
+  state.svgPolygons = [];
+  state.scrambleSlots = state.scramble.tiles.map((tile) => {
+    const box = tileBox(tile);
+    const slot = document.createElementNS("http://www.w3.org/2000/svg", "svg");
+    slot.setAttribute("x", box.x);
+    slot.setAttribute("y", box.y);
+    slot.setAttribute("width", box.width);
+    slot.setAttribute("height", box.height);
+    slot.setAttribute("preserveAspectRatio", "none");
+
+    const image = document.createElementNS("http://www.w3.org/2000/svg", "image");
+    image.setAttribute("href", state.image.src);
+    image.setAttribute("width", w);
+    image.setAttribute("height", h);
+    slot.appendChild(image);
+    state.svg.appendChild(slot);
+    return slot;
+  });
+
+  state.scrambleIdx = 0;
+  showScramble();
+  state.uncoveringPaused = false;
 }
 
 async function loadCovering() {
   const w = state.image.naturalWidth || 1;
   const h = state.image.naturalHeight || 1;
   const n = Number(state.inputObjectCount.value);
+  state.scramble = null;
+  if (frameModes.includes(state.inputObjectType.value)) {
+    return loadFrames(w, h, n);
+  }
+  state.frames = [];
+  if (state.inputObjectType.value === "Zoom") {
+    return loadZoom();
+  }
+  if (state.inputObjectType.value === "Scramble") {
+    return loadScramble(w, h, n);
+  }
+  return loadPolygons(w, h, n, state.inputObjectType.value);
+}
+
+async function loadPolygons(w, h, n, objectType) {
   debug(`Requesting covering for ${w}x${h} with ${n}.`);
 
   const polygons = await invoke("load_covering", {
     n: n,
     width: w,
     height: h,
-    objectType: state.inputObjectType.value,
+    objectType: objectType,
   });
 
   state.polygons = polygons.map((polygon) => {
@@ -126,6 +342,7 @@ async function loadCovering() {
   }
 
   state.svgPolygonsHideIdx = 0;
+  state.uncoveringPaused = false;
   state.image.removeAttribute("hidden");
 }
 
@@ -136,12 +353,26 @@ function getRandomColorHex() {
     .padStart(6, "0")}`;
 }
 
+function showNextHint() {
+  const hint = state.hints.shift();
+  if (hint !== undefined) {
+    state.hintSpan.textContent = hint;
+  }
+  state.hintButton.hidden = state.hints.length === 0;
+}
+
 async function executeAction(actionIdentifier) {
   debug(`Executing action ${actionIdentifier}`);
   switch (actionIdentifier) {
     case Action.uncover:
       uncoverNext();
       break;
+    case Action.toggleUncovering:
+      await toggleUncovering();
+      break;
+    case Action.speedUpUncovering:
+      await invoke("speed_up_uncovering", { factor: 1.5 });
+      break;
     case Action.reset:
       coverFull();
       break;
@@ -177,6 +408,12 @@ async function executeAction(actionIdentifier) {
         executeIfSettingsChanged(() => {
           getImage(0).then(() => loadCovering());
         });
+        executeIfFolderScanChanged(() => {
+          // Hand-selected images and decks are not affected by scanning.
+          if (state.imageSource === "folder") {
+            invoke("get_image_paths", { forceSelection: false, folder: true, verbose: state.inputVerbose.checked });
+          }
+        });
       }
       break;
     case Action.settingsReset:
@@ -199,6 +436,10 @@ function registerControlButtons() {
 
 function registerKeyboard() {
   document.addEventListener("keyup", (event) => {
+    // Typing into the settings is not meant as game actions.
+    if (event.target?.closest("input, select, textarea") !== null) {
+      return;
+    }
     // By default, react to the key as specified by the 'data-event' in the html.
     // But additionally react to some special keys:
     let action = event.key.toLowerCase();
@@ -234,7 +475,7 @@ function registerTouch() {
     if (e.target?.closest(".settings") !== null) {
       return;
     }
-    if (e.target?.classList.contains("answer")) {
+    if (e.target?.closest(".qna") !== null) {
       return;
     }
     executeAction(Action.uncover);
@@ -304,6 +545,19 @@ function registerTouch() {
   });
 }
 
+// Puts back the settings a deck has replaced, including changes made while it was played.
+async function restoreSettingsAfterDeck() {
+  const saved = state.settingsBeforeDeck;
+  if (saved === null) {
+    return;
+  }
+  state.inputObjectType.value = (await state.store.get("object_type")) ?? saved.object_type;
+  state.inputObjectCount.value = (await state.store.get("object_count")) ?? saved.object_count;
+  state.inputUncoveringOrder.value = (await state.store.get("uncovering_order")) ?? saved.uncovering_order;
+  await setUncoveringOrder(state);
+  state.settingsBeforeDeck = null;
+}
+
 function registerTauriEvents() {
   function tfListen(eventName, fun) {
     listen(eventName, (event) => {
@@ -312,26 +566,62 @@ function registerTauriEvents() {
     });
   }
 
-  tfListen("image-paths-updated", (event) => {
+  tfListen("image-paths-updated", async (event) => {
     const [container, count] = event.payload;
     const plural = count > 1 ? "s" : "";
-    if (container) {
+    if (state.deckTitle !== null) {
+      state.locationSpan.textContent = `Deck: ${state.deckTitle}.`;
+      state.deckTitle = null;
+      state.imageSource = "deck";
+    } else if (container) {
+      await restoreSettingsAfterDeck();
       state.locationSpan.textContent = `Image${plural} from: ${container}.`;
+      state.imageSource = "folder";
     } else {
+      await restoreSettingsAfterDeck();
       state.locationSpan.textContent = `Image${plural} hand-selected.`;
+      state.imageSource = "files";
     }
     state.progressSpan.textContent = "";
     getImage(0).then(() => loadCovering());
   });
 
-  tfListen("image-paths-failed", (_) => {
+  // A deck's settings apply while it is played, but are not persisted.
+  tfListen("deck-loaded", (event) => {
+    const deck = event.payload;
+    state.deckTitle = deck.title ?? "Untitled";
+    // Another deck may replace the current one, keep the user's settings from before the first.
+    state.settingsBeforeDeck ??= {
+      object_type: state.inputObjectType.value,
+      object_count: state.inputObjectCount.value,
+      uncovering_order: state.inputUncoveringOrder.value,
+    };
+    if (deck.covering_type !== null) {
+      state.inputObjectType.value = deck.covering_type;
+    }
+    if (deck.object_count !== null) {
+      state.inputObjectCount.value = deck.object_count;
+    }
+    if (deck.uncovering_order !== null) {
+      state.inputUncoveringOrder.value = deck.uncovering_order;
+      setUncoveringOrder(state);
+    }
+  });
+
+  tfListen("image-paths-failed", async (_) => {
+    await restoreSettingsAfterDeck();
     state.locationSpan.textContent = "Exemplary images.";
+    state.imageSource = "examples";
     state.progressSpan.textContent = "";
     // Since no images have been loaded, this will return randomly picked
     // exemplary images.
     getImage(0).then(() => loadCovering());
   });
 
+  tfListen("uncover-next", (_) => {
+    uncoverNext();
+  });
+
   tfListen("image-index", (event) => {
     const indexState = event.payload;
     state.progressSpan.textContent = `${indexState[0] + 1} / ${indexState[1]}`;
@@ -348,7 +638,13 @@ window.addEventListener("DOMContentLoaded", async () => {
   state.slider = document.querySelector("#sliderN");
   state.progressSpan = document.querySelector("#progress");
   state.locationSpan = document.querySelector("#location");
+  state.subfolderSpan = document.querySelector("#subfolder");
   state.qnaAnswersDiv = document.querySelector("#answers");
+  state.questionSpan = document.querySelector("#question-span");
+  state.hintButton = document.querySelector("#hint");
+  state.hintSpan = document.querySelector("#hint-span");
+  state.explanationSpan = document.querySelector("#explanation-span");
+  state.hintButton.addEventListener("pointerup", showNextHint);
   state.selectFolder = document.querySelector("#input-select-images");
   state.selectImages = document.querySelector("#input-select-images-individual");
 
@@ -363,9 +659,20 @@ window.addEventListener("DOMContentLoaded", async () => {
   state.settingsDiv = document.querySelector("#settings");
   state.inputShowControls = document.querySelector("#input-show-controls");
   state.inputVerbose = document.querySelector("#input-verbose");
+  state.inputSeed = document.querySelector("#input-seed");
+  state.inputMaxResolution = document.querySelector("#input-max-resolution");
+  state.inputScanDepth = document.querySelector("#input-scan-depth");
+  state.inputScanInclude = document.querySelector("#input-scan-include");
+  state.inputScanExclude = document.querySelector("#input-scan-exclude");
+  state.inputScanSkipHidden = document.querySelector("#input-scan-skip-hidden");
   state.inputObjectType = document.querySelector("#input-object-type");
   state.inputObjectCount = document.querySelector("#input-object-count");
-  state.inputQuizYear = document.querySelector("#input-quiz-year");
+  state.inputPointSampling = document.querySelector("#input-point-sampling");
+  state.inputMinDistance = document.querySelector("#input-min-distance");
+  state.inputQuizTypes = document.querySelectorAll("input.quiz-type");
+  state.inputUncoveringStrategy = document.querySelector("#input-uncovering-strategy");
+  state.inputUncoveringInterval = document.querySelector("#input-uncovering-interval");
+  state.inputUncoveringOrder = document.querySelector("#input-uncovering-order");
 
   // Before setting up everything, load the current settings,
   // which may have been persisted from a previous execution.
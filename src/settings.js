@@ -1,8 +1,107 @@
 import { isMobile } from "./utils.js";
 
-const { debug } = window.__TAURI__.log;
+const { invoke } = window.__TAURI__.core;
+const { debug, error } = window.__TAURI__.log;
+const { message } = window.__TAURI__.dialog;
 
 let settingsDirty = false;
+let folderScanDirty = false;
+
+// An empty input means that a random seed shall be used.
+function seedFromInput(input) {
+  return input.value === "" ? null : Number(input.value);
+}
+
+// An empty input means that the size of the largest screen shall be used.
+function maxResolutionFromInput(input) {
+  return input.value === "" ? null : Number(input.value);
+}
+
+// Uncovering strategy as expected by the backend.
+function uncoveringStrategy(state) {
+  if (state.inputUncoveringStrategy.value === "Timed") {
+    return { Timed: { interval_ms: Math.round(Number(state.inputUncoveringInterval.value) * 1000) } };
+  }
+  return "Manual";
+}
+
+// Point sampling as expected by the backend, the distance is relative to the image's shorter side.
+function pointSampling(state) {
+  if (state.inputPointSampling.value === "PoissonDisk") {
+    const minDistance = state.inputMinDistance.value;
+    return { PoissonDisk: { min_distance: minDistance === "" ? null : Number(minDistance) / 100 } };
+  }
+  return "Grid";
+}
+
+// Store key of a quiz type checkbox, e.g. 'quiz_guess_year' for '#input-quiz-year'.
+function quizTypeKey(input) {
+  return `quiz_guess_${input.id.replace("input-quiz-", "").replaceAll("-", "_")}`;
+}
+
+// Folder scan settings as expected by the backend, patterns are separated by commas.
+function folderScan(state) {
+  const patterns = (input) =>
+    input.value
+      .split(",")
+      .map((p) => p.trim())
+      .filter((p) => p !== "");
+  return {
+    max_depth: Number(state.inputScanDepth.value),
+    include: patterns(state.inputScanInclude),
+    exclude: patterns(state.inputScanExclude),
+    skip_hidden: state.inputScanSkipHidden.checked,
+  };
+}
+
+async function setFolderScan(state) {
+  try {
+    await invoke("set_folder_scan", { scan: folderScan(state) });
+  } catch (e) {
+    error(`Invalid folder scan settings: ${e}`);
+    message(`${e}`, { title: "Invalid folder settings", kind: "error" });
+  }
+}
+
+async function setSeed(state) {
+  try {
+    await invoke("set_seed", { seed: seedFromInput(state.inputSeed) });
+  } catch (e) {
+    error(`Invalid seed: ${e}`);
+    message(`${e}`, { title: "Invalid seed", kind: "error" });
+  }
+}
+
+async function setPointSampling(state) {
+  try {
+    await invoke("set_point_sampling", { sampling: pointSampling(state) });
+  } catch (e) {
+    error(`Invalid point sampling: ${e}`);
+    message(`${e}`, { title: "Invalid point sampling", kind: "error" });
+  }
+}
+
+async function setUncoveringStrategy(state) {
+  try {
+    await invoke("set_uncovering_strategy", { strategy: uncoveringStrategy(state) });
+  } catch (e) {
+    error(`Invalid uncovering strategy: ${e}`);
+    message(`${e}`, { title: "Invalid uncovering interval", kind: "error" });
+  }
+}
+
+async function setUncoveringOrder(state) {
+  await invoke("set_uncovering_order", { order: state.inputUncoveringOrder.value });
+}
+
+async function setMaxResolution(state) {
+  try {
+    await invoke("set_max_resolution", { resolution: maxResolutionFromInput(state.inputMaxResolution) });
+  } catch (e) {
+    error(`Invalid maximum resolution: ${e}`);
+    message(`${e}`, { title: "Invalid maximum resolution", kind: "error" });
+  }
+}
 
 function showControlButtons(show) {
   for (const element of document.querySelectorAll(".controls-optional")) {
@@ -40,6 +139,49 @@ async function loadSettings(state) {
     }
   });
 
+  await state.store.get("point_sampling").then((v) => {
+    if (v !== undefined) {
+      state.inputPointSampling.value = v;
+    } else {
+      state.inputPointSampling.value = "Grid";
+    }
+  });
+
+  await state.store.get("min_point_distance").then((v) => {
+    if (v !== undefined) {
+      state.inputMinDistance.value = v;
+    } else {
+      state.inputMinDistance.value = "";
+    }
+  });
+  await setPointSampling(state);
+
+  await state.store.get("uncovering_strategy").then((v) => {
+    if (v !== undefined) {
+      state.inputUncoveringStrategy.value = v;
+    } else {
+      state.inputUncoveringStrategy.value = "Manual";
+    }
+  });
+
+  await state.store.get("uncovering_interval").then((v) => {
+    if (v !== undefined) {
+      state.inputUncoveringInterval.value = v;
+    } else {
+      state.inputUncoveringInterval.value = 3;
+    }
+  });
+  await setUncoveringStrategy(state);
+
+  await state.store.get("uncovering_order").then((v) => {
+    if (v !== undefined) {
+      state.inputUncoveringOrder.value = v;
+    } else {
+      state.inputUncoveringOrder.value = "Random";
+    }
+  });
+  await setUncoveringOrder(state);
+
   await state.store.get("verbose").then((v) => {
     if (v !== undefined) {
       state.inputVerbose.checked = JSON.parse(v);
@@ -48,14 +190,67 @@ async function loadSettings(state) {
     }
   });
 
-  await state.store.get("quiz_guess_year").then((v) => {
+  for (const input of state.inputQuizTypes) {
+    await state.store.get(quizTypeKey(input)).then((v) => {
+      if (v !== undefined) {
+        input.checked = JSON.parse(v);
+      } else {
+        input.checked = false;
+      }
+    });
+  }
+
+  await state.store.get("scan_depth").then((v) => {
+    if (v !== undefined) {
+      state.inputScanDepth.value = v;
+    } else {
+      state.inputScanDepth.value = 0;
+    }
+  });
+
+  await state.store.get("scan_include").then((v) => {
+    if (v !== undefined) {
+      state.inputScanInclude.value = v;
+    } else {
+      state.inputScanInclude.value = "";
+    }
+  });
+
+  await state.store.get("scan_exclude").then((v) => {
     if (v !== undefined) {
-      state.inputQuizYear.checked = JSON.parse(v);
+      state.inputScanExclude.value = v;
     } else {
-      state.inputQuizYear.checked = false;
+      state.inputScanExclude.value = "";
     }
   });
 
+  await state.store.get("scan_skip_hidden").then((v) => {
+    if (v !== undefined) {
+      state.inputScanSkipHidden.checked = JSON.parse(v);
+    } else {
+      state.inputScanSkipHidden.checked = true;
+    }
+  });
+  await setFolderScan(state);
+
+  await state.store.get("seed").then((v) => {
+    if (v !== undefined) {
+      state.inputSeed.value = v;
+    } else {
+      state.inputSeed.value = "";
+    }
+  });
+  await setSeed(state);
+
+  await state.store.get("max_resolution").then((v) => {
+    if (v !== undefined) {
+      state.inputMaxResolution.value = v;
+    } else {
+      state.inputMaxResolution.value = "";
+    }
+  });
+  await setMaxResolution(state);
+
   debug(`Loaded initial settings: ${JSON.stringify(await state.store.entries(), null, "  ")}.`);
 }
 
@@ -70,6 +265,31 @@ function initializeSettingsListeners(state) {
     state.store.set("verbose", e.target.checked);
   });
 
+  state.inputSeed.addEventListener("change", (e) => {
+    state.store.set("seed", e.target.value);
+    setSeed(state);
+    settingsDirty = true;
+  });
+
+  state.inputMaxResolution.addEventListener("change", (e) => {
+    state.store.set("max_resolution", e.target.value);
+    setMaxResolution(state);
+    settingsDirty = true;
+  });
+
+  for (const [input, key] of [
+    [state.inputScanDepth, "scan_depth"],
+    [state.inputScanInclude, "scan_include"],
+    [state.inputScanExclude, "scan_exclude"],
+    [state.inputScanSkipHidden, "scan_skip_hidden"],
+  ]) {
+    input.addEventListener("change", (e) => {
+      state.store.set(key, e.target.type === "checkbox" ? e.target.checked : e.target.value);
+      setFolderScan(state);
+      folderScanDirty = true;
+    });
+  }
+
   state.inputObjectType.addEventListener("input", (e) => {
     state.store.set("object_type", e.target.value);
     settingsDirty = true;
@@ -81,10 +301,40 @@ function initializeSettingsListeners(state) {
     settingsDirty = true;
   });
 
-  state.inputQuizYear.addEventListener("input", (e) => {
-    state.store.set("quiz_guess_year", e.target.checked);
+  state.inputPointSampling.addEventListener("input", (e) => {
+    state.store.set("point_sampling", e.target.value);
+    setPointSampling(state);
+    settingsDirty = true;
+  });
+
+  state.inputMinDistance.addEventListener("change", (e) => {
+    state.store.set("min_point_distance", e.target.value);
+    setPointSampling(state);
     settingsDirty = true;
   });
+
+  state.inputUncoveringStrategy.addEventListener("input", (e) => {
+    state.store.set("uncovering_strategy", e.target.value);
+    setUncoveringStrategy(state);
+  });
+
+  state.inputUncoveringInterval.addEventListener("change", (e) => {
+    state.store.set("uncovering_interval", e.target.value);
+    setUncoveringStrategy(state);
+  });
+
+  state.inputUncoveringOrder.addEventListener("input", (e) => {
+    state.store.set("uncovering_order", e.target.value);
+    setUncoveringOrder(state);
+    settingsDirty = true;
+  });
+
+  for (const input of state.inputQuizTypes) {
+    input.addEventListener("input", (e) => {
+      state.store.set(quizTypeKey(e.target), e.target.checked);
+      settingsDirty = true;
+    });
+  }
 }
 
 // Execute 'fun' if settings have been changed that require content updates.
@@ -95,9 +345,24 @@ function executeIfSettingsChanged(fun) {
   }
 }
 
+// Execute 'fun' if settings have been changed that require scanning folders again.
+function executeIfFolderScanChanged(fun) {
+  if (folderScanDirty) {
+    fun();
+    folderScanDirty = false;
+  }
+}
+
 async function resetSettings(state) {
   await state.store.clear();
   await loadSettings(state);
 }
 
-export { executeIfSettingsChanged, initializeSettingsListeners, loadSettings, resetSettings };
+export {
+  executeIfFolderScanChanged,
+  executeIfSettingsChanged,
+  initializeSettingsListeners,
+  loadSettings,
+  resetSettings,
+  setUncoveringOrder,
+};